@@ -112,8 +112,22 @@ pub const MARKER_HSO: u8 = 116;  // Handshake out
 pub const MARKER_HSI: u8 = 117;  // Handshake in
 pub const MARKER_ACK: u8 = 118;  // Acknowledge
 
-// Reserved markers: 119-127 (9 slots available)
-// Note: Future versions should use different indices for MARKER_X
+// Version control markers - each version stamps payloads with its own #X# index
+// so a payload decoded against the wrong charset is detectable (V1 uses MARKER_X)
+pub const MARKER_X_V2: u8 = 119;
+pub const MARKER_X_V3: u8 = 120;
+pub const MARKER_X_V4: u8 = 121;
+
+// Mode switches - internal to their strategies, so they have no marker string
+pub const MARKER_NUM: u8 = 123;  // Packed digit run
+pub const MARKER_DICT: u8 = 124; // Dictionary header and references
+
+// Quote-safe escapes (#Q# and #E# cover the quotes)
+pub const MARKER_BSL: u8 = 125;  // Backslash \
+pub const MARKER_CHR: u8 = 126;  // Character by hex code, e.g. #CHR#09 for a tab
+
+// Reserved markers: 122, 127 (2 slots available)
+pub const RESERVED_MARKERS: [u8; 2] = [122, 127];
 
 // Marker strings for preprocessing
 pub const MARKERS: &[(&str, u8)] = &[
@@ -136,6 +150,8 @@ pub const MARKERS: &[(&str, u8)] = &[
     ("#HSO#", MARKER_HSO),
     ("#HSI#", MARKER_HSI),
     ("#ACK#", MARKER_ACK),
+    ("#BSL#", MARKER_BSL),
+    ("#CHR#", MARKER_CHR),
 ];
 
 /// Replace marker strings with their corresponding byte values for encoding
@@ -166,6 +182,53 @@ pub fn postprocess_markers(text: &str) -> String {
     result
 }
 
+/// Look up the marker string for an extension index
+pub fn marker_name(index: u8) -> Option<&'static str> {
+    MARKERS.iter()
+        .find(|(_, marker_index)| *marker_index == index)
+        .map(|(marker_str, _)| *marker_str)
+}
+
+/// Locate marker strings in text, returning (byte position, marker index) pairs
+///
+/// Uses the same scan as encoding: a candidate runs from `#` to the next `#`,
+/// and candidates that are not markers are consumed as literal text.
+pub fn scan_markers(text: &str) -> Vec<(usize, u8)> {
+    let mut found = Vec::new();
+    let mut chars = text.char_indices();
+    
+    while let Some((start, ch)) = chars.next() {
+        if ch != '#' {
+            continue;
+        }
+        
+        let mut end = start + 1;
+        for (pos, next_ch) in chars.by_ref() {
+            end = pos + next_ch.len_utf8();
+            if next_ch == '#' {
+                break;
+            }
+        }
+        
+        let candidate = &text[start..end];
+        if let Some((_, marker_index)) = MARKERS.iter().find(|(marker_str, _)| *marker_str == candidate) {
+            found.push((start, *marker_index));
+        }
+    }
+    
+    found
+}
+
+/// 32-bit FNV-1a hash, used for short stable identifiers
+pub(crate) fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u32 {
+    let mut hash: u32 = 0x811c9dc5;
+    for byte in bytes {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    hash
+}
+
 /// Check if a byte value is a valid extension marker
 pub const fn is_extension_marker(index: u8) -> bool {
     index >= 100 && index <= 127
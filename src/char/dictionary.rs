@@ -0,0 +1,244 @@
+//! Dictionary compression for frequently repeated substrings
+//!
+//! `DictionaryStrategy` replaces dictionary entries in the index stream with
+//! the #DICT# mode index (124) followed by the entry number, two indices for
+//! the whole substring. Every payload starts with #DICT# and the dictionary id,
+//! so the decoder knows which dictionary to expand with.
+//!
+//! Entry numbers are part of the payload format: append to a dictionary, but
+//! never reorder or remove entries once payloads exist.
+
+use super::charset::{marker_name, scan_markers, MARKER_DICT};
+use super::extensions::EncodingStrategy;
+use super::versions::Asc100Version;
+use crate::Asc100Error;
+
+/// Id of the built-in dictionary
+pub const DEFAULT_DICTIONARY_ID: u8 = 0;
+
+/// Most entries a dictionary can hold; entry number 127 escapes a literal #DICT#
+pub const MAX_ENTRIES: usize = 127;
+
+const LITERAL: u8 = 127;
+
+/// Entries of the built-in dictionary, in payload order
+pub const DEFAULT_ENTRIES: &[&str] = &[
+    "https://", "http://", "localhost", "www.", ".com", ".org", ".net", "127.0.0.1",
+    "sec:", "config:", "/api/", "api/", "json", "true", "false", "null",
+    "name", "value", "user", "error", "status", "message", "data", "type",
+    "the ", "and ", "ing ", "tion", "ment", "that ", "with ", "for ",
+    "this ", "from ", "have ", "://", "    ", "\n    ", "id=", "date",
+    "time", "path", "file", "index", "content", "application/", "text/", "Content-Type",
+    "Authorization", "Bearer ", "password", "token", "example", "default", "version", "namespace",
+];
+
+/// A numbered list of substrings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dictionary {
+    id: u8,
+    entries: Vec<String>,
+}
+
+impl Dictionary {
+    /// User dictionary; ids 1-127 are free, 0 is the built-in dictionary
+    ///
+    /// Entries need at least 2 characters, must be distinct and must not
+    /// contain marker text such as `#V#`.
+    pub fn new(id: u8, entries: &[&str]) -> Result<Self, Asc100Error> {
+        if id == DEFAULT_DICTIONARY_ID || id > 127 {
+            return Err(Asc100Error::InvalidDictionary(format!("id {} is not in 1-127", id)));
+        }
+        Self::with_id(id, entries)
+    }
+
+    /// The built-in dictionary
+    pub fn builtin() -> Self {
+        Self::with_id(DEFAULT_DICTIONARY_ID, DEFAULT_ENTRIES).expect("built-in entries are valid")
+    }
+
+    fn with_id(id: u8, entries: &[&str]) -> Result<Self, Asc100Error> {
+        if entries.len() > MAX_ENTRIES {
+            return Err(Asc100Error::InvalidDictionary(format!(
+                "{} entries, at most {} allowed", entries.len(), MAX_ENTRIES
+            )));
+        }
+        for (number, entry) in entries.iter().enumerate() {
+            if entry.chars().count() < 2 {
+                return Err(Asc100Error::InvalidDictionary(format!("entry {} is shorter than 2 characters", number)));
+            }
+            // Marker text is tokenized before compression, so it would never match
+            if let Some((_, marker)) = scan_markers(entry).first() {
+                return Err(Asc100Error::InvalidDictionary(format!(
+                    "entry {} contains the marker {}", number, marker_name(*marker).unwrap_or("")
+                )));
+            }
+            if entries[..number].contains(entry) {
+                return Err(Asc100Error::InvalidDictionary(format!("entry {} repeats {:?}", number, entry)));
+            }
+        }
+        Ok(Self { id, entries: entries.iter().map(|entry| entry.to_string()).collect() })
+    }
+
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Entries as a version's indices
+    fn compile(&self, version: &Asc100Version) -> Result<Vec<Vec<u8>>, Asc100Error> {
+        self.entries.iter()
+            .map(|entry| {
+                entry.chars()
+                    .map(|ch| match version.lookup.get(ch as usize) {
+                        Some(&index) if index != 255 => Ok(index),
+                        _ => Err(Asc100Error::InvalidDictionary(format!(
+                            "{:?} in {:?} is not in the {} charset", ch, entry, version.name
+                        ))),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// A dictionary compiled for one version
+struct Compiled {
+    id: u8,
+    entries: Vec<Vec<u8>>,
+    /// Entry numbers by first index, longest entry first
+    by_first: Vec<Vec<u8>>,
+}
+
+impl Compiled {
+    fn new(dictionary: &Dictionary, version: &Asc100Version) -> Result<Self, Asc100Error> {
+        let entries = dictionary.compile(version)?;
+        let mut by_first = vec![Vec::new(); 128];
+        for (number, entry) in entries.iter().enumerate() {
+            by_first[entry[0] as usize].push(number as u8);
+        }
+        for numbers in &mut by_first {
+            // Stable, so equal lengths keep dictionary order
+            numbers.sort_by_key(|&number| std::cmp::Reverse(entries[number as usize].len()));
+        }
+        Ok(Self { id: dictionary.id, entries, by_first })
+    }
+
+    /// Longest entry at the start of `indices`
+    fn longest_match(&self, indices: &[u8]) -> Option<u8> {
+        self.by_first.get(indices[0] as usize)?
+            .iter()
+            .copied()
+            .find(|&number| indices.starts_with(&self.entries[number as usize]))
+    }
+}
+
+/// Dictionary strategy - replaces dictionary entries with two-index references
+///
+/// Encoding uses the most recently added dictionary (the built-in one by
+/// default); decoding accepts any added dictionary, picked by the id in the
+/// payload header.
+///
+/// Entry numbers are raw index values, so a strategy wrapped around this one
+/// sees arbitrary index values. Only `VersionedStrategy` and `EofStrategy` may
+/// wrap it: they ignore index values. Any strategy may sit inside, including
+/// `DigitRunStrategy`, because an inner #DICT# index is escaped. Payloads
+/// using it must be re-encoded rather than transcoded to another version.
+pub struct DictionaryStrategy<S: EncodingStrategy> {
+    pub inner: S,
+    version: Asc100Version,
+    dictionaries: Vec<Compiled>,
+}
+
+impl<S: EncodingStrategy> DictionaryStrategy<S> {
+    /// Compress with the built-in dictionary for the given version
+    pub fn new(inner: S, version: &Asc100Version) -> Self {
+        let builtin = Compiled::new(&Dictionary::builtin(), version).expect("built-in entries are in every charset");
+        Self { inner, version: *version, dictionaries: vec![builtin] }
+    }
+
+    /// Compress with `dictionary` from now on, replacing any dictionary with the
+    /// same id; the others stay available for decoding
+    pub fn with_dictionary(mut self, dictionary: &Dictionary) -> Result<Self, Asc100Error> {
+        let compiled = Compiled::new(dictionary, &self.version)?;
+        self.dictionaries.retain(|existing| existing.id != compiled.id);
+        self.dictionaries.push(compiled);
+        Ok(self)
+    }
+
+    /// Id written to payloads by this strategy
+    pub fn dictionary_id(&self) -> u8 {
+        self.encoding().id
+    }
+
+    fn encoding(&self) -> &Compiled {
+        // User ids are never 0, so the built-in dictionary is never removed
+        self.dictionaries.last().expect("a dictionary is always present")
+    }
+}
+
+impl<S: EncodingStrategy> EncodingStrategy for DictionaryStrategy<S> {
+    fn preprocess(&self, input: &str) -> Result<String, Asc100Error> {
+        self.inner.preprocess(input)
+    }
+
+    fn postprocess(&self, output: &str) -> String {
+        self.inner.postprocess(output)
+    }
+
+    fn supports_index(&self, index: u8) -> bool {
+        self.inner.supports_index(index)
+    }
+
+    fn encode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, Asc100Error> {
+        let indices = self.inner.encode_indices(indices)?;
+        let dictionary = self.encoding();
+
+        let mut compressed = Vec::with_capacity(indices.len() + 2);
+        compressed.extend([MARKER_DICT, dictionary.id]);
+        let mut i = 0;
+        while i < indices.len() {
+            if indices[i] == MARKER_DICT {
+                compressed.extend([MARKER_DICT, LITERAL]);
+                i += 1;
+            } else if let Some(number) = dictionary.longest_match(&indices[i..]) {
+                compressed.extend([MARKER_DICT, number]);
+                i += dictionary.entries[number as usize].len();
+            } else {
+                compressed.push(indices[i]);
+                i += 1;
+            }
+        }
+        Ok(compressed)
+    }
+
+    fn decode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, Asc100Error> {
+        let id = match indices.get(..2) {
+            Some(&[MARKER_DICT, id]) => id,
+            _ => return Err(Asc100Error::InvalidDictionary("missing dictionary header".to_string())),
+        };
+        let dictionary = self.dictionaries.iter()
+            .find(|dictionary| dictionary.id == id)
+            .ok_or(Asc100Error::UnknownDictionary(id))?;
+
+        let mut expanded = Vec::with_capacity(indices.len() * 2);
+        let mut rest = indices[2..].iter();
+        while let Some(&index) = rest.next() {
+            if index != MARKER_DICT {
+                expanded.push(index);
+                continue;
+            }
+            match rest.next() {
+                Some(&LITERAL) => expanded.push(MARKER_DICT),
+                Some(&number) => match dictionary.entries.get(number as usize) {
+                    Some(entry) => expanded.extend_from_slice(entry),
+                    None => return Err(Asc100Error::InvalidDictionary(format!("no entry {} in dictionary {}", number, id))),
+                },
+                None => return Err(Asc100Error::InvalidDictionary("reference is truncated".to_string())),
+            }
+        }
+        self.inner.decode_indices(expanded)
+    }
+}
@@ -50,6 +50,72 @@ pub trait EncodingStrategy {
     fn preprocess(&self, input: &str) -> Result<String, crate::Asc100Error>;
     fn postprocess(&self, output: &str) -> String;
     fn supports_index(&self, index: u8) -> bool;
+    
+    /// Adjust the index stream after tokenization, before bit packing
+    fn encode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        Ok(indices)
+    }
+    
+    /// Validate or adjust the unpacked index stream before characters are restored
+    fn decode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        Ok(indices)
+    }
+}
+
+/// A strategy chosen at runtime, e.g. from a [`StrategyRegistry`](crate::char::registry::StrategyRegistry)
+pub type DynStrategy = Box<dyn EncodingStrategy + Send + Sync>;
+
+/// A filter chosen at runtime
+pub type DynFilter = Box<dyn FilterStrategy + Send + Sync>;
+
+impl<S: EncodingStrategy + ?Sized> EncodingStrategy for Box<S> {
+    fn preprocess(&self, input: &str) -> Result<String, crate::Asc100Error> {
+        (**self).preprocess(input)
+    }
+    
+    fn postprocess(&self, output: &str) -> String {
+        (**self).postprocess(output)
+    }
+    
+    fn supports_index(&self, index: u8) -> bool {
+        (**self).supports_index(index)
+    }
+    
+    fn encode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        (**self).encode_indices(indices)
+    }
+    
+    fn decode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        (**self).decode_indices(indices)
+    }
+}
+
+impl<S: EncodingStrategy + ?Sized> EncodingStrategy for std::sync::Arc<S> {
+    fn preprocess(&self, input: &str) -> Result<String, crate::Asc100Error> {
+        (**self).preprocess(input)
+    }
+    
+    fn postprocess(&self, output: &str) -> String {
+        (**self).postprocess(output)
+    }
+    
+    fn supports_index(&self, index: u8) -> bool {
+        (**self).supports_index(index)
+    }
+    
+    fn encode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        (**self).encode_indices(indices)
+    }
+    
+    fn decode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        (**self).decode_indices(indices)
+    }
+}
+
+impl<F: FilterStrategy + ?Sized> FilterStrategy for Box<F> {
+    fn handle_char(&self, ch: char) -> FilterAction {
+        (**self).handle_char(ch)
+    }
 }
 
 // ============================================================================
@@ -144,6 +210,684 @@ impl<F: FilterStrategy> EncodingStrategy for ExtensionsStrategy<F> {
     }
 }
 
+/// Marker selection for a restricted extensions strategy
+#[derive(Debug, Clone)]
+pub enum MarkerList {
+    /// Only these marker indices are accepted
+    Allow(Vec<u8>),
+    /// Every marker except these indices is accepted
+    Deny(Vec<u8>),
+}
+
+impl MarkerList {
+    pub fn permits(&self, index: u8) -> bool {
+        match self {
+            MarkerList::Allow(markers) => markers.contains(&index),
+            MarkerList::Deny(markers) => !markers.contains(&index),
+        }
+    }
+}
+
+/// Restricted extensions strategy - supports markers from an allow-list or deny-list
+/// and rejects any other marker on both encode and decode
+pub struct RestrictedStrategy<F: FilterStrategy> {
+    pub filter: F,
+    pub markers: MarkerList,
+}
+
+impl<F: FilterStrategy> RestrictedStrategy<F> {
+    /// Byte offset in `input` of the character the filter turned into the
+    /// text at `filtered_position`, so errors point into the caller's input
+    fn input_position(&self, input: &str, filtered_position: usize) -> usize {
+        let mut filtered_len = 0;
+        for (position, ch) in input.char_indices() {
+            filtered_len += match self.filter.handle_char(ch) {
+                FilterAction::Keep => ch.len_utf8(),
+                FilterAction::Replace(replacement) => replacement.len(),
+                FilterAction::Skip | FilterAction::Error(_) => 0,
+            };
+            if filtered_len > filtered_position {
+                return position;
+            }
+        }
+        input.len()
+    }
+}
+
+impl<F: FilterStrategy> EncodingStrategy for RestrictedStrategy<F> {
+    fn preprocess(&self, input: &str) -> Result<String, crate::Asc100Error> {
+        let filtered = self.filter.filter_input_with_context(input, "RestrictedStrategy")?;
+        
+        if let Some((position, marker)) = crate::char::charset::scan_markers(&filtered)
+            .into_iter()
+            .find(|(_, index)| !self.markers.permits(*index))
+        {
+            let position = self.input_position(input, position);
+            return Err(crate::Asc100Error::RejectedMarker { marker, position });
+        }
+
+        Ok(filtered)
+    }
+    
+    fn postprocess(&self, output: &str) -> String {
+        output.to_string()
+    }
+    
+    fn supports_index(&self, index: u8) -> bool {
+        index < 100 || (index <= 127 && self.markers.permits(index))
+    }
+    
+    fn decode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        // Track the position each index will occupy in the decoded text
+        let mut position = 0;
+        for &index in &indices {
+            if index < 100 {
+                position += 1;
+                continue;
+            }
+            if !self.markers.permits(index) {
+                return Err(crate::Asc100Error::RejectedMarker { marker: index, position });
+            }
+            position += crate::char::charset::marker_name(index).map_or(0, str::len);
+        }
+        Ok(indices)
+    }
+}
+
+/// Where a version stamp is placed in the index stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StampPlacement {
+    Prefix,
+    Suffix,
+}
+
+/// Versioned strategy - stamps payloads with the version's #X# control marker
+/// and rejects payloads whose stamp is missing or belongs to another version
+pub struct VersionedStrategy<S: EncodingStrategy> {
+    pub inner: S,
+    pub control_marker: u8,
+    pub placement: StampPlacement,
+}
+
+impl<S: EncodingStrategy> VersionedStrategy<S> {
+    /// Wrap a strategy with a prefix stamp for the given version
+    pub fn new(inner: S, version: &crate::char::versions::Asc100Version) -> Self {
+        Self {
+            inner,
+            control_marker: version.control_marker,
+            placement: StampPlacement::Prefix,
+        }
+    }
+    
+    /// Place the stamp at the end of the payload instead of the start
+    pub fn suffix(mut self) -> Self {
+        self.placement = StampPlacement::Suffix;
+        self
+    }
+}
+
+impl<S: EncodingStrategy> EncodingStrategy for VersionedStrategy<S> {
+    fn preprocess(&self, input: &str) -> Result<String, crate::Asc100Error> {
+        self.inner.preprocess(input)
+    }
+    
+    fn postprocess(&self, output: &str) -> String {
+        self.inner.postprocess(output)
+    }
+    
+    fn supports_index(&self, index: u8) -> bool {
+        self.inner.supports_index(index)
+    }
+    
+    fn encode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        let mut indices = self.inner.encode_indices(indices)?;
+        match self.placement {
+            StampPlacement::Prefix => indices.insert(0, self.control_marker),
+            StampPlacement::Suffix => indices.push(self.control_marker),
+        }
+        Ok(indices)
+    }
+    
+    fn decode_indices(&self, mut indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        let found = match self.placement {
+            StampPlacement::Prefix => indices.first().copied(),
+            StampPlacement::Suffix => indices.last().copied(),
+        };
+        
+        if found != Some(self.control_marker) {
+            return Err(crate::Asc100Error::InvalidVersionStamp {
+                expected: self.control_marker,
+                found: found.filter(|index| *index >= 100),
+            });
+        }
+        
+        match self.placement {
+            StampPlacement::Prefix => { indices.remove(0); }
+            StampPlacement::Suffix => { indices.pop(); }
+        }
+        self.inner.decode_indices(indices)
+    }
+}
+
+/// Characters carried as markers by the quote-safe strategy, with the text
+/// `render_quote_safe` writes for them
+const QUOTE_MARKERS: [(char, u8, &str); 3] = [
+    ('"', crate::char::charset::MARKER_Q, "#Q#"),
+    ('\'', crate::char::charset::MARKER_E, "#E#"),
+    ('\\', crate::char::charset::MARKER_BSL, "#BSL#"),
+];
+
+/// Quote-safe strategy - carries `"`, `'` and `\` as the #Q#, #E# and #BSL#
+/// markers on encode, and restores the characters on decode
+///
+/// Those markers mean the quote characters inside a quote-safe payload, so
+/// their text in the input (such as protocol text escaping `#` as #E#) is
+/// carried as literal characters rather than as markers.
+pub struct QuoteSafeStrategy<F: FilterStrategy> {
+    pub filter: F,
+    /// Charset indices of `"`, `'` and `\` in the target version
+    pub quote_indices: [u8; 3],
+}
+
+impl<F: FilterStrategy> QuoteSafeStrategy<F> {
+    pub fn new(filter: F, version: &crate::char::versions::Asc100Version) -> Self {
+        Self {
+            filter,
+            quote_indices: QUOTE_MARKERS.map(|(ch, _, _)| version.lookup[ch as usize]),
+        }
+    }
+}
+
+impl<F: FilterStrategy> EncodingStrategy for QuoteSafeStrategy<F> {
+    fn preprocess(&self, input: &str) -> Result<String, crate::Asc100Error> {
+        self.filter.filter_input_with_context(input, "QuoteSafeStrategy")
+    }
+    
+    fn postprocess(&self, output: &str) -> String {
+        output.to_string()
+    }
+    
+    fn supports_index(&self, index: u8) -> bool {
+        index <= 127 && !QUOTE_MARKERS.iter().any(|(_, marker, _)| *marker == index)
+    }
+    
+    fn encode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        Ok(indices.into_iter()
+            .map(|index| match self.quote_indices.iter().position(|&quote| quote == index) {
+                Some(i) => QUOTE_MARKERS[i].1,
+                None => index,
+            })
+            .collect())
+    }
+    
+    fn decode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        Ok(indices.into_iter()
+            .map(|index| match QUOTE_MARKERS.iter().position(|(_, marker, _)| *marker == index) {
+                Some(i) => self.quote_indices[i],
+                None => index,
+            })
+            .collect())
+    }
+}
+
+/// Names `render_quote_safe` writes, each starting with `#`
+fn rendered_escapes() -> impl Iterator<Item = &'static str> {
+    QUOTE_MARKERS.iter().map(|(_, _, name)| *name).chain(["#NL#", "#CHR#"])
+}
+
+/// Render decoded text so it can be pasted inside a JSON string, a single-quoted
+/// shell argument or an XStream quoted value without further escaping
+///
+/// Quotes and backslashes become #Q#, #E# and #BSL#, newlines become #NL#, and
+/// the other control characters (tab, carriage return, `\0`, `\x01`) become
+/// #CHR# with two hex digits. A `#` that would read as one of these escapes is
+/// written as #CHR#23, so [`parse_quote_safe`] restores the exact text.
+pub fn render_quote_safe(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for (pos, ch) in text.char_indices() {
+        match ch {
+            '\n' => result.push_str("#NL#"),
+            '#' if rendered_escapes().any(|name| text[pos..].starts_with(name)) => result.push_str("#CHR#23"),
+            c if c.is_ascii_control() => result.push_str(&format!("#CHR#{:02X}", c as u8)),
+            c => match QUOTE_MARKERS.iter().find(|(quote, _, _)| *quote == c) {
+                Some((_, _, name)) => result.push_str(name),
+                None => result.push(c),
+            },
+        }
+    }
+    result
+}
+
+/// Restore text rendered by [`render_quote_safe`]
+///
+/// A `#` that does not start one of the rendered escapes is kept as text.
+pub fn parse_quote_safe(rendered: &str) -> String {
+    let mut result = String::with_capacity(rendered.len());
+    let mut rest = rendered;
+    while let Some(start) = rest.find('#') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let restored = [("#NL#", '\n')].into_iter()
+            .chain(QUOTE_MARKERS.iter().map(|(ch, _, name)| (*name, *ch)))
+            .find(|(name, _)| rest.starts_with(name))
+            .map(|(name, ch)| (name.len(), ch))
+            .or_else(|| {
+                let code = rest.strip_prefix("#CHR#")?.get(..2)?;
+                let byte = u8::from_str_radix(code, 16).ok()?;
+                Some(("#CHR#".len() + 2, byte as char))
+            });
+        match restored {
+            Some((len, ch)) => {
+                result.push(ch);
+                rest = &rest[len..];
+            }
+            None => {
+                result.push('#');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Line ending rebuilt when decoding #NL# markers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+    Cr,
+    /// The first line ending of the original input, recorded in the payload
+    Original,
+}
+
+/// Newline strategy - normalizes `\r\n`, `\r` and `\n` to #NL# on encode and
+/// rebuilds the chosen line ending on decode
+///
+/// With `LineEnding::Original` the encoder records the input's first line ending
+/// as a header (#NL# followed by the raw ending), so both sides must use it.
+/// Otherwise the payload is identical whatever line endings the input used.
+pub struct NewlineStrategy<F: FilterStrategy> {
+    pub filter: F,
+    pub ending: LineEnding,
+    /// Charset indices of `\n` and `\r` in the target version
+    pub lf: u8,
+    pub cr: u8,
+}
+
+impl<F: FilterStrategy> NewlineStrategy<F> {
+    pub fn new(filter: F, version: &crate::char::versions::Asc100Version, ending: LineEnding) -> Self {
+        Self {
+            filter,
+            ending,
+            lf: version.lookup[b'\n' as usize],
+            cr: version.lookup[b'\r' as usize],
+        }
+    }
+    
+    fn ending_indices(&self, ending: LineEnding) -> Vec<u8> {
+        match ending {
+            LineEnding::Lf | LineEnding::Original => vec![self.lf],
+            LineEnding::CrLf => vec![self.cr, self.lf],
+            LineEnding::Cr => vec![self.cr],
+        }
+    }
+}
+
+impl<F: FilterStrategy> EncodingStrategy for NewlineStrategy<F> {
+    fn preprocess(&self, input: &str) -> Result<String, crate::Asc100Error> {
+        self.filter.filter_input_with_context(input, "NewlineStrategy")
+    }
+    
+    fn postprocess(&self, output: &str) -> String {
+        output.to_string()
+    }
+    
+    fn supports_index(&self, index: u8) -> bool {
+        index <= 127
+    }
+    
+    fn encode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        let mut result = Vec::with_capacity(indices.len() + 3);
+        let mut original = None;
+        let mut iter = indices.into_iter().peekable();
+        
+        while let Some(index) = iter.next() {
+            if index == self.cr {
+                let crlf = iter.next_if_eq(&self.lf).is_some();
+                original.get_or_insert(if crlf { LineEnding::CrLf } else { LineEnding::Cr });
+                result.push(crate::char::charset::MARKER_NL);
+            } else if index == self.lf {
+                original.get_or_insert(LineEnding::Lf);
+                result.push(crate::char::charset::MARKER_NL);
+            } else {
+                result.push(index);
+            }
+        }
+        
+        if self.ending == LineEnding::Original {
+            // Header: #NL# then the raw ending, which never appears in a normalized body
+            let mut header = vec![crate::char::charset::MARKER_NL];
+            if let Some(ending) = original {
+                header.extend(self.ending_indices(ending));
+            }
+            result.splice(0..0, header);
+        }
+        Ok(result)
+    }
+    
+    fn decode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        let mut body = &indices[..];
+        let mut ending = self.ending;
+        
+        if self.ending == LineEnding::Original {
+            if body.first() != Some(&crate::char::charset::MARKER_NL) {
+                return Err(crate::Asc100Error::MissingNewlineFlag);
+            }
+            body = &body[1..];
+            let raw = body.iter().take_while(|&&index| index == self.cr || index == self.lf).count();
+            ending = match &body[..raw] {
+                [] => LineEnding::Lf,
+                [cr, lf] if *cr == self.cr && *lf == self.lf => LineEnding::CrLf,
+                [cr] if *cr == self.cr => LineEnding::Cr,
+                [lf] if *lf == self.lf => LineEnding::Lf,
+                _ => return Err(crate::Asc100Error::MissingNewlineFlag),
+            };
+            body = &body[raw..];
+        }
+        
+        let newline = self.ending_indices(ending);
+        let mut result = Vec::with_capacity(body.len());
+        for &index in body {
+            if index == crate::char::charset::MARKER_NL {
+                result.extend_from_slice(&newline);
+            } else {
+                result.push(index);
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// What to do with content after the first #EOF# marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrailerPolicy {
+    /// Drop it silently
+    Discard,
+    /// Fail with `TrailingContent`
+    Reject,
+}
+
+/// EOF strategy - treats the first #EOF# as a hard terminator on decode
+///
+/// With `append_eof` the encoder always ends the payload with #EOF# and the
+/// decoder rejects payloads without one, which detects truncation.
+///
+/// The inner strategy always sees the whole stream: #EOF# is appended before
+/// the inner strategy encodes, and the inner strategy decodes the whole stream,
+/// trailer included, before it is split. A suffix version stamp from an inner
+/// `VersionedStrategy` therefore stays after any trailer and is checked even
+/// when content follows #EOF#.
+pub struct EofStrategy<S: EncodingStrategy> {
+    pub inner: S,
+    pub trailer: TrailerPolicy,
+    pub append: bool,
+}
+
+impl<S: EncodingStrategy> EofStrategy<S> {
+    /// Terminate at #EOF#, discarding anything after it
+    pub fn new(inner: S) -> Self {
+        Self { inner, trailer: TrailerPolicy::Discard, append: false }
+    }
+    
+    /// Fail when content follows #EOF#
+    pub fn reject_trailer(mut self) -> Self {
+        self.trailer = TrailerPolicy::Reject;
+        self
+    }
+    
+    /// Always end payloads with #EOF# and require it when decoding
+    pub fn append_eof(mut self) -> Self {
+        self.append = true;
+        self
+    }
+    
+    /// Decode a payload, returning the content before #EOF# and, when the
+    /// marker is present, the decoded content after it
+    pub fn decode_with_trailer(
+        &self,
+        encoded: &str,
+        version: &crate::char::versions::Asc100Version
+    ) -> Result<(String, Option<String>), crate::Asc100Error> {
+        self.decode_with_trailer_in(encoded, &crate::codec::Codec::new(*version))
+    }
+    
+    /// Like `decode_with_trailer`, with the codec's alphabet and canonical check
+    pub fn decode_with_trailer_in(
+        &self,
+        encoded: &str,
+        codec: &crate::codec::Codec
+    ) -> Result<(String, Option<String>), crate::Asc100Error> {
+        let charset = &codec.version().charset;
+        let (body, trailer) = self.split_at_eof(self.inner.decode_indices(codec.unpack(encoded)?)?)?;
+        let body = crate::render_indices(&body, charset, &self.inner)?;
+        let trailer = trailer
+            .map(|trailer| crate::render_indices(&trailer, charset, &self.inner))
+            .transpose()?;
+        Ok((body, trailer))
+    }
+    
+    /// Split indices at the first #EOF#, dropping the marker itself
+    fn split_at_eof(&self, mut indices: Vec<u8>) -> Result<(Vec<u8>, Option<Vec<u8>>), crate::Asc100Error> {
+        match indices.iter().position(|&index| index == crate::char::charset::MARKER_EOF) {
+            Some(eof) => {
+                let trailer = indices.split_off(eof + 1);
+                indices.pop();
+                Ok((indices, Some(trailer)))
+            }
+            None if self.append => Err(crate::Asc100Error::MissingEof),
+            None => Ok((indices, None)),
+        }
+    }
+}
+
+impl<S: EncodingStrategy> EncodingStrategy for EofStrategy<S> {
+    fn preprocess(&self, input: &str) -> Result<String, crate::Asc100Error> {
+        self.inner.preprocess(input)
+    }
+    
+    fn postprocess(&self, output: &str) -> String {
+        self.inner.postprocess(output)
+    }
+    
+    fn supports_index(&self, index: u8) -> bool {
+        index == crate::char::charset::MARKER_EOF || self.inner.supports_index(index)
+    }
+    
+    fn encode_indices(&self, mut indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        if self.append && indices.last() != Some(&crate::char::charset::MARKER_EOF) {
+            indices.push(crate::char::charset::MARKER_EOF);
+        }
+        self.inner.encode_indices(indices)
+    }
+    
+    fn decode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        let (body, trailer) = self.split_at_eof(self.inner.decode_indices(indices)?)?;
+        if self.trailer == TrailerPolicy::Reject && trailer.as_ref().is_some_and(|t| !t.is_empty()) {
+            // Position of the trailer in the decoded text, counting the #EOF# marker
+            let position = body.iter()
+                .chain(std::iter::once(&crate::char::charset::MARKER_EOF))
+                .map(|&index| crate::char::charset::marker_name(index).map_or(1, str::len))
+                .sum();
+            return Err(crate::Asc100Error::TrailingContent { position });
+        }
+        Ok(body)
+    }
+}
+
+/// Shortest digit run packed by default; shorter runs cost more packed
+pub const DEFAULT_MIN_DIGIT_RUN: usize = 5;
+
+/// Longest run a single packed segment can carry (its count is one index)
+pub const MAX_DIGIT_RUN: usize = 127;
+
+/// Digit-run strategy - packs runs of digits at 3 digits per 10 bits
+///
+/// A packed run is the #NUM# mode marker (index 123), the digit count as one
+/// index, then the digit bits in 7-bit indices: 10 bits per 3 digits, 7 bits
+/// for 2 and 4 bits for 1 left over, zero-padded. The count tells the decoder
+/// where the run ends, since the digit bits can take any index value.
+///
+/// Packed indices are raw bits, so a strategy wrapped around this one sees
+/// arbitrary index values. Only `DictionaryStrategy`, `VersionedStrategy` and
+/// `EofStrategy` may wrap it: they escape or ignore index values. Combined
+/// with a dictionary, put the dictionary outside. Payloads using it must be
+/// re-encoded rather than transcoded to another version.
+pub struct DigitRunStrategy<S: EncodingStrategy> {
+    pub inner: S,
+    /// Index of each digit 0-9 in the version's charset
+    pub digit_indices: [u8; 10],
+    /// Shortest run that is packed
+    pub min_run: usize,
+}
+
+impl<S: EncodingStrategy> DigitRunStrategy<S> {
+    /// Pack runs of `DEFAULT_MIN_DIGIT_RUN` or more digits for the given version
+    pub fn new(inner: S, version: &crate::char::versions::Asc100Version) -> Self {
+        let mut digit_indices = [0u8; 10];
+        for (digit, slot) in digit_indices.iter_mut().enumerate() {
+            *slot = version.lookup[b'0' as usize + digit];
+        }
+        Self { inner, digit_indices, min_run: DEFAULT_MIN_DIGIT_RUN }
+    }
+    
+    /// Only pack runs of at least `min_run` digits
+    pub fn min_run(mut self, min_run: usize) -> Self {
+        self.min_run = min_run.max(1);
+        self
+    }
+    
+    fn digit_value(&self, index: u8) -> Option<u8> {
+        self.digit_indices.iter().position(|&digit| digit == index).map(|digit| digit as u8)
+    }
+    
+    /// Append one packed segment of at most `MAX_DIGIT_RUN` digits
+    fn pack_run(&self, digits: &[u8], out: &mut Vec<u8>) {
+        let mut bits = Vec::with_capacity(digits.len() * 4);
+        for group in digits.chunks(3) {
+            let (value, width) = match *group {
+                [a, b, c] => (a as u16 * 100 + b as u16 * 10 + c as u16, 10),
+                [a, b] => (a as u16 * 10 + b as u16, 7),
+                [a] => (a as u16, 4),
+                _ => unreachable!(),
+            };
+            for i in (0..width).rev() {
+                bits.push(((value >> i) & 1) as u8);
+            }
+        }
+        
+        out.push(crate::char::charset::MARKER_NUM);
+        out.push(digits.len() as u8);
+        for chunk in bits.chunks(7) {
+            let value = chunk.iter().enumerate().fold(0u8, |acc, (i, &bit)| acc | (bit << (6 - i)));
+            out.push(value);
+        }
+    }
+    
+    /// Expand the segment starting after a #NUM# marker, returning the indices read
+    fn unpack_run(&self, packed: &[u8], out: &mut Vec<u8>) -> Result<usize, crate::Asc100Error> {
+        let malformed = |reason: &str| crate::Asc100Error::MalformedDigitRun(reason.to_string());
+        
+        let count = *packed.first().ok_or_else(|| malformed("missing digit count"))? as usize;
+        if count == 0 {
+            return Err(malformed("empty run"));
+        }
+        let width = count / 3 * 10 + [0, 4, 7][count % 3];
+        let chunks = width.saturating_add(6) / 7;
+        let data = packed.get(1..1 + chunks).ok_or_else(|| malformed("run is truncated"))?;
+        
+        let bits: Vec<u8> = data.iter().flat_map(|&index| (0..7).rev().map(move |i| (index >> i) & 1)).collect();
+        let mut pos = 0;
+        let mut remaining = count;
+        while remaining > 0 {
+            let (digits, group_width, limit) = match remaining {
+                1 => (1, 4, 10),
+                2 => (2, 7, 100),
+                _ => (3, 10, 1000),
+            };
+            let value = bits[pos..pos + group_width].iter().fold(0u16, |acc, &bit| (acc << 1) | bit as u16);
+            if value >= limit {
+                return Err(malformed("group value out of range"));
+            }
+            for place in (0..digits).rev() {
+                out.push(self.digit_indices[(value / 10u16.pow(place)) as usize % 10]);
+            }
+            pos += group_width;
+            remaining -= digits as usize;
+        }
+        if bits[width..].contains(&1) {
+            return Err(malformed("padding bits are not zero"));
+        }
+        Ok(1 + chunks)
+    }
+}
+
+impl<S: EncodingStrategy> EncodingStrategy for DigitRunStrategy<S> {
+    fn preprocess(&self, input: &str) -> Result<String, crate::Asc100Error> {
+        self.inner.preprocess(input)
+    }
+    
+    fn postprocess(&self, output: &str) -> String {
+        self.inner.postprocess(output)
+    }
+    
+    fn supports_index(&self, index: u8) -> bool {
+        self.inner.supports_index(index)
+    }
+    
+    fn encode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        let indices = self.inner.encode_indices(indices)?;
+        if indices.contains(&crate::char::charset::MARKER_NUM) {
+            return Err(crate::Asc100Error::MalformedDigitRun("input already contains the mode marker".to_string()));
+        }
+        
+        let mut packed = Vec::with_capacity(indices.len());
+        let mut i = 0;
+        while i < indices.len() {
+            let run = indices[i..].iter().take_while(|&&index| self.digit_value(index).is_some()).count();
+            if run < self.min_run {
+                packed.extend_from_slice(&indices[i..i + run.max(1)]);
+                i += run.max(1);
+                continue;
+            }
+            let digits: Vec<u8> = indices[i..i + run].iter().filter_map(|&index| self.digit_value(index)).collect();
+            for segment in digits.chunks(MAX_DIGIT_RUN) {
+                if segment.len() < self.min_run {
+                    packed.extend(segment.iter().map(|&digit| self.digit_indices[digit as usize]));
+                } else {
+                    self.pack_run(segment, &mut packed);
+                }
+            }
+            i += run;
+        }
+        Ok(packed)
+    }
+    
+    fn decode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        let mut expanded = Vec::with_capacity(indices.len() * 2);
+        let mut i = 0;
+        while i < indices.len() {
+            if indices[i] == crate::char::charset::MARKER_NUM {
+                i += 1 + self.unpack_run(&indices[i + 1..], &mut expanded)?;
+            } else {
+                expanded.push(indices[i]);
+                i += 1;
+            }
+        }
+        self.inner.decode_indices(expanded)
+    }
+}
+
 // ============================================================================
 // CONVENIENCE CONSTRUCTORS
 // ============================================================================
@@ -182,4 +926,26 @@ impl ExtensionsStrategy<StripFilter> {
     pub fn strip() -> Self {
         Self { filter: StripFilter }
     }
-}
\ No newline at end of file
+}
+
+impl<F: FilterStrategy> ExtensionsStrategy<F> {
+    /// Restrict this strategy to the given marker indices
+    pub fn allow_markers(self, markers: &[u8]) -> RestrictedStrategy<F> {
+        RestrictedStrategy { filter: self.filter, markers: MarkerList::Allow(markers.to_vec()) }
+    }
+    
+    /// Accept every marker except the given indices
+    pub fn deny_markers(self, markers: &[u8]) -> RestrictedStrategy<F> {
+        RestrictedStrategy { filter: self.filter, markers: MarkerList::Deny(markers.to_vec()) }
+    }
+    
+    /// Carry quotes and backslashes as markers for the given version
+    pub fn quote_safe(self, version: &crate::char::versions::Asc100Version) -> QuoteSafeStrategy<F> {
+        QuoteSafeStrategy::new(self.filter, version)
+    }
+    
+    /// Normalize line endings to #NL#, rebuilding `ending` on decode
+    pub fn normalize_newlines(self, version: &crate::char::versions::Asc100Version, ending: LineEnding) -> NewlineStrategy<F> {
+        NewlineStrategy::new(self.filter, version, ending)
+    }
+}
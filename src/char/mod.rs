@@ -1,6 +1,8 @@
 pub mod charset;
 pub mod versions;
 pub mod extensions;
+pub mod registry;
+pub mod dictionary;
 
 // Re-export commonly used items
 pub use charset::{
@@ -13,4 +15,6 @@ pub use charset::{
     preprocess_markers,
     postprocess_markers,
     MARKERS,
+    marker_name,
+    scan_markers,
 };
\ No newline at end of file
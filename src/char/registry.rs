@@ -0,0 +1,218 @@
+//! Name-based strategy and version registries
+//!
+//! `StrategyRegistry` builds strategies from names like `"extensions+sanitize"`
+//! or `"core+strip"`: a strategy name, optionally followed by `+` and a filter
+//! name (defaulting to `strict`). Third-party strategies and filters register
+//! under their own names.
+//!
+//! `VersionRegistry` holds the built-in charset versions plus custom ones, and
+//! backs the tagged payload format `fingerprint:payload` (8 hex digits of
+//! `Asc100Version::fingerprint`) used by `decode_auto`. A registered version
+//! resolves tags written in any of its packings.
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use super::extensions::{
+    CoreStrategy, DynFilter, DynStrategy, EncodingStrategy, ExtensionsStrategy, SanitizeFilter, StrictFilter,
+    StripFilter,
+};
+use super::versions::{Asc100Version, Packing, V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL};
+use crate::Asc100Error;
+
+type StrategyBuilder = Box<dyn Fn(DynFilter) -> DynStrategy + Send + Sync>;
+type FilterBuilder = Box<dyn Fn() -> DynFilter + Send + Sync>;
+
+/// Filter used when a name has no `+filter` part
+pub const DEFAULT_FILTER: &str = "strict";
+
+/// Registry of strategy and filter constructors
+pub struct StrategyRegistry {
+    strategies: HashMap<String, StrategyBuilder>,
+    filters: HashMap<String, FilterBuilder>,
+}
+
+impl Default for StrategyRegistry {
+    /// Registry with the built-in `core` / `extensions` strategies and
+    /// `strict` / `sanitize` / `strip` filters
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry
+            .register_strategy("core", |filter| Box::new(CoreStrategy { filter }))
+            .register_strategy("extensions", |filter| Box::new(ExtensionsStrategy { filter }))
+            .register_filter("strict", || Box::new(StrictFilter))
+            .register_filter("sanitize", || Box::new(SanitizeFilter))
+            .register_filter("strip", || Box::new(StripFilter));
+        registry
+    }
+}
+
+impl StrategyRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry without any built-ins
+    pub fn empty() -> Self {
+        Self { strategies: HashMap::new(), filters: HashMap::new() }
+    }
+
+    /// Register a strategy constructor, replacing any previous one with that name
+    pub fn register_strategy<B>(&mut self, name: &str, builder: B) -> &mut Self
+    where
+        B: Fn(DynFilter) -> DynStrategy + Send + Sync + 'static,
+    {
+        self.strategies.insert(name.to_string(), Box::new(builder));
+        self
+    }
+
+    /// Register a filter constructor, replacing any previous one with that name
+    pub fn register_filter<B>(&mut self, name: &str, builder: B) -> &mut Self
+    where
+        B: Fn() -> DynFilter + Send + Sync + 'static,
+    {
+        self.filters.insert(name.to_string(), Box::new(builder));
+        self
+    }
+
+    /// Build a strategy from `name` or `name+filter`
+    pub fn build(&self, spec: &str) -> Result<DynStrategy, Asc100Error> {
+        let (name, filter) = spec.split_once('+').unwrap_or((spec, DEFAULT_FILTER));
+        let strategy = self.strategies.get(name.trim())
+            .ok_or_else(|| Asc100Error::UnknownStrategy(spec.to_string()))?;
+        let filter = self.filters.get(filter.trim())
+            .ok_or_else(|| Asc100Error::UnknownStrategy(spec.to_string()))?;
+        Ok(strategy(filter()))
+    }
+
+    /// Registered strategy names, sorted
+    pub fn strategy_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.strategies.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Registered filter names, sorted
+    pub fn filter_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.filters.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+/// Build a strategy by name from the built-in registry
+pub fn strategy_by_name(spec: &str) -> Result<DynStrategy, Asc100Error> {
+    StrategyRegistry::default().build(spec)
+}
+
+/// Registry of charset versions, looked up by name or fingerprint
+#[derive(Clone)]
+pub struct VersionRegistry {
+    versions: Vec<Asc100Version>,
+}
+
+impl Default for VersionRegistry {
+    /// Registry with the four built-in versions
+    fn default() -> Self {
+        Self { versions: vec![V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL] }
+    }
+}
+
+impl VersionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The process-wide registry used by `decode_auto`
+    pub fn global() -> &'static RwLock<VersionRegistry> {
+        static GLOBAL: OnceLock<RwLock<VersionRegistry>> = OnceLock::new();
+        GLOBAL.get_or_init(|| RwLock::new(VersionRegistry::default()))
+    }
+
+    /// Add a version; registering the same name, charset and control marker
+    /// again is a no-op
+    ///
+    /// A version whose fingerprint, in any packing, matches a differently
+    /// named registered version is rejected, since tags could not tell them apart.
+    pub fn register(&mut self, version: Asc100Version) -> Result<(), Asc100Error> {
+        if let Some(existing) = self.by_name(version.name) {
+            if existing.charset == version.charset && existing.control_marker == version.control_marker {
+                return Ok(());
+            }
+            return Err(Asc100Error::DuplicateVersion(version.name.to_string()));
+        }
+        for packed in packings(version) {
+            if let Some(existing) = self.by_fingerprint(packed.fingerprint()) {
+                return Err(Asc100Error::DuplicateVersion(existing.name.to_string()));
+            }
+        }
+        self.versions.push(version);
+        Ok(())
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<Asc100Version> {
+        self.versions.iter().find(|version| version.name == name).copied()
+    }
+
+    /// Registered version, in whichever packing has this fingerprint
+    pub fn by_fingerprint(&self, fingerprint: u32) -> Option<Asc100Version> {
+        self.versions.iter()
+            .flat_map(|&version| packings(version))
+            .find(|version| version.fingerprint() == fingerprint)
+    }
+
+    pub fn versions(&self) -> &[Asc100Version] {
+        &self.versions
+    }
+
+    /// Split a tagged payload into its registered version and the bare payload
+    pub fn resolve_tag<'a>(&self, tagged: &'a str) -> Result<(Asc100Version, &'a str), Asc100Error> {
+        let (tag, payload) = tagged.split_once(':')
+            .ok_or_else(|| Asc100Error::InvalidVersionTag(tagged.chars().take(16).collect()))?;
+        let fingerprint = u32::from_str_radix(tag, 16)
+            .ok()
+            .filter(|_| tag.len() == 8)
+            .ok_or_else(|| Asc100Error::InvalidVersionTag(tag.to_string()))?;
+        let version = self.by_fingerprint(fingerprint)
+            .ok_or_else(|| Asc100Error::UnknownVersion(tag.to_string()))?;
+        Ok((version, payload))
+    }
+}
+
+/// A version in each packing
+fn packings(version: Asc100Version) -> [Asc100Version; 3] {
+    [Packing::Fixed, Packing::Variable, Packing::Shift].map(|packing| Asc100Version { packing, ..version })
+}
+
+/// Register a version in the global registry
+pub fn register_version(version: Asc100Version) -> Result<(), Asc100Error> {
+    VersionRegistry::global().write().unwrap_or_else(|e| e.into_inner()).register(version)
+}
+
+/// Look up a version by name in the global registry
+pub fn version_by_name(name: &str) -> Option<Asc100Version> {
+    VersionRegistry::global().read().unwrap_or_else(|e| e.into_inner()).by_name(name)
+}
+
+/// Encode with the strict core strategy and prefix the payload with the version tag
+pub fn encode_tagged(input: &str, version: &Asc100Version) -> Result<String, Asc100Error> {
+    encode_tagged_with(input, version, &CoreStrategy::strict())
+}
+
+pub fn encode_tagged_with<S: EncodingStrategy + ?Sized>(
+    input: &str,
+    version: &Asc100Version,
+    strategy: &S
+) -> Result<String, Asc100Error> {
+    Ok(format!("{:08x}:{}", version.fingerprint(), version.encode_with(input, strategy)?))
+}
+
+/// Decode a tagged payload with the version named by its tag
+pub fn decode_auto(tagged: &str) -> Result<String, Asc100Error> {
+    decode_auto_with(tagged, &CoreStrategy::strict())
+}
+
+pub fn decode_auto_with<S: EncodingStrategy + ?Sized>(tagged: &str, strategy: &S) -> Result<String, Asc100Error> {
+    let (version, payload) = VersionRegistry::global().read().unwrap_or_else(|e| e.into_inner()).resolve_tag(tagged)?;
+    version.decode_with(payload, strategy)
+}
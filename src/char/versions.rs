@@ -1,12 +1,48 @@
 use super::charset::{create_base_charset, swap_chars, swap_ranges, build_lookup_table};
+use super::charset::{MARKER_X, MARKER_X_V2, MARKER_X_V3, MARKER_X_V4, RESERVED_MARKERS};
+
+/// How indices are packed into the payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Packing {
+    /// Every index takes 7 bits
+    Fixed,
+    /// Prefix code where low indices are shorter, see [`crate::varlen`]
+    Variable,
+    /// 6-bit codes with shift/latch between two planes, see [`crate::shift`]
+    Shift,
+}
 
 #[derive(Copy, Clone)]
 pub struct Asc100Version {
     pub name: &'static str,
     pub charset: [char; 100],
     pub lookup: [u8; 128],
+    /// Index of this version's #X# control marker, used for version stamps
+    pub control_marker: u8,
+    /// Packing used when encoding; decoding accepts either format
+    pub packing: Packing,
+}
+
+impl std::fmt::Debug for Asc100Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Asc100Version")
+            .field("name", &self.name)
+            .field("fingerprint", &format_args!("{:08x}", self.fingerprint()))
+            .field("control_marker", &self.control_marker)
+            .field("packing", &self.packing)
+            .finish()
+    }
 }
 
+/// Marker indices a version may stamp with: the #X# slots of the built-in
+/// versions and the reserved indices, none of which mean anything else
+pub const CONTROL_MARKERS: [u8; 6] = [
+    MARKER_X, MARKER_X_V2, MARKER_X_V3, MARKER_X_V4, RESERVED_MARKERS[0], RESERVED_MARKERS[1],
+];
+
+/// Control marker given to custom versions, one no built-in version stamps with
+pub const CUSTOM_CONTROL_MARKER: u8 = RESERVED_MARKERS[1];
+
 const fn create_v1_standard() -> [char; 100] {
     let base = create_base_charset();
     // V1: Swap space (index 0) with tilde (index 94)
@@ -43,47 +79,141 @@ pub const V1_STANDARD: Asc100Version = Asc100Version {
     name: "v1_standard",
     charset: create_v1_standard(),
     lookup: build_lookup_table(create_v1_standard()),
+    control_marker: MARKER_X,
+    packing: Packing::Fixed,
 };
 
 pub const V2_NUMBERS: Asc100Version = Asc100Version {
     name: "v2_numbers_first",
     charset: create_v2_numbers_first(),
     lookup: build_lookup_table(create_v2_numbers_first()),
+    control_marker: MARKER_X_V2,
+    packing: Packing::Fixed,
 };
 
 pub const V3_LOWERCASE: Asc100Version = Asc100Version {
     name: "v3_lowercase_first",
     charset: create_v3_lowercase_first(),
     lookup: build_lookup_table(create_v3_lowercase_first()),
+    control_marker: MARKER_X_V3,
+    packing: Packing::Fixed,
 };
 
 pub const V4_URL: Asc100Version = Asc100Version {
     name: "v4_url_optimized",
     charset: create_v4_url_optimized(),
     lookup: build_lookup_table(create_v4_url_optimized()),
+    control_marker: MARKER_X_V4,
+    packing: Packing::Fixed,
 };
 
 impl Asc100Version {
+    /// Build a version from a runtime charset ordering
+    ///
+    /// The charset must be a permutation of the canonical 100 characters.
+    /// Custom versions stamp payloads with [`CUSTOM_CONTROL_MARKER`] unless
+    /// `with_control_marker` picks another one.
+    pub fn custom(name: &'static str, charset: [char; 100]) -> Result<Self, crate::Asc100Error> {
+        let canonical = create_base_charset();
+        let mut duplicates = Vec::new();
+        let mut unexpected = Vec::new();
+        let mut seen = [false; 128];
+        
+        for &ch in &charset {
+            if !canonical.contains(&ch) {
+                unexpected.push(ch);
+            } else if seen[ch as usize] {
+                if !duplicates.contains(&ch) {
+                    duplicates.push(ch);
+                }
+            } else {
+                seen[ch as usize] = true;
+            }
+        }
+        let missing: Vec<char> = canonical.iter().copied().filter(|&ch| !seen[ch as usize]).collect();
+        
+        if !duplicates.is_empty() || !missing.is_empty() || !unexpected.is_empty() {
+            return Err(crate::Asc100Error::InvalidCharset { duplicates, missing, unexpected });
+        }
+        
+        Ok(Self {
+            name,
+            charset,
+            lookup: build_lookup_table(charset),
+            control_marker: CUSTOM_CONTROL_MARKER,
+            packing: Packing::Fixed,
+        })
+    }
+    
+    /// Like [`custom`](Self::custom) for a name only known at runtime
+    ///
+    /// Names are interned for the life of the process, so building versions
+    /// with the same name again does not allocate again.
+    pub fn custom_owned(name: impl Into<String>, charset: [char; 100]) -> Result<Self, crate::Asc100Error> {
+        Self::custom(intern_name(name.into()), charset)
+    }
+    
+    /// Unnamed fixed-packing version over raw tables, for the low-level API
+    pub(crate) const fn from_tables(charset: [char; 100], lookup: [u8; 128]) -> Self {
+        Self { name: "custom", charset, lookup, control_marker: MARKER_X, packing: Packing::Fixed }
+    }
+    
+    /// Stable identifier derived from the charset ordering, control marker and packing
+    ///
+    /// Versions that encode identically share a fingerprint, whatever their name.
+    pub fn fingerprint(&self) -> u32 {
+        let packing = match self.packing {
+            Packing::Fixed => 0,
+            Packing::Variable => 1,
+            Packing::Shift => 2,
+        };
+        super::charset::fnv1a(self.charset.iter().map(|&ch| ch as u8).chain([self.control_marker, packing]))
+    }
+    
+    /// This version with variable-length packing, so its ordering affects size
+    pub const fn variable(self) -> Self {
+        Self { packing: Packing::Variable, ..self }
+    }
+    
+    /// This version with shift-state 6-bit coding, for lowercase-dominated text
+    pub const fn shifted(self) -> Self {
+        Self { packing: Packing::Shift, ..self }
+    }
+    
+    /// Use another marker index for version stamps, one of [`CONTROL_MARKERS`]
+    pub fn with_control_marker(mut self, marker: u8) -> Result<Self, crate::Asc100Error> {
+        if !CONTROL_MARKERS.contains(&marker) {
+            return Err(crate::Asc100Error::InvalidControlMarker(marker));
+        }
+        self.control_marker = marker;
+        Ok(self)
+    }
+    
     pub fn encode(&self, input: &str) -> Result<String, crate::Asc100Error> {
-        // Use the working strategy-based algorithm with CoreStrategy
-        let strategy = crate::char::extensions::CoreStrategy::strict();
-        crate::encode_with_strategy(input, &self.charset, &self.lookup, &strategy)
+        // Default codec for this version: strict CoreStrategy
+        crate::codec::Codec::new(*self).encode(input)
     }
     
     pub fn decode(&self, encoded: &str) -> Result<String, crate::Asc100Error> {
-        // Use the working strategy-based algorithm with CoreStrategy
-        let strategy = crate::char::extensions::CoreStrategy::strict();
-        crate::decode_with_strategy(encoded, &self.charset, &strategy)
+        crate::codec::Codec::new(*self).decode(encoded)
     }
     
     /// Encode with a custom strategy - convenience method to reduce parameter burden
-    pub fn encode_with<S: crate::char::extensions::EncodingStrategy>(&self, input: &str, strategy: &S) -> Result<String, crate::Asc100Error> {
-        crate::encode_with_strategy(input, &self.charset, &self.lookup, strategy)
+    pub fn encode_with<S: crate::char::extensions::EncodingStrategy + ?Sized>(&self, input: &str, strategy: &S) -> Result<String, crate::Asc100Error> {
+        crate::codec::Codec::new(*self).encode_with(input, strategy)
     }
     
     /// Decode with a custom strategy - convenience method to reduce parameter burden
-    pub fn decode_with<S: crate::char::extensions::EncodingStrategy>(&self, encoded: &str, strategy: &S) -> Result<String, crate::Asc100Error> {
-        crate::decode_with_strategy(encoded, &self.charset, strategy)
+    pub fn decode_with<S: crate::char::extensions::EncodingStrategy + ?Sized>(&self, encoded: &str, strategy: &S) -> Result<String, crate::Asc100Error> {
+        crate::codec::Codec::new(*self).decode_with(encoded, strategy)
+    }
+    
+    /// Decode and render quotes and control characters as markers, for pasting
+    /// inside quoted values; reads both plain and quote-safe payloads
+    pub fn decode_quote_safe(&self, encoded: &str) -> Result<String, crate::Asc100Error> {
+        let strategy = crate::char::extensions::QuoteSafeStrategy::new(crate::char::extensions::StrictFilter, self);
+        let decoded = self.decode_with(encoded, &strategy)?;
+        Ok(crate::char::extensions::render_quote_safe(&decoded))
     }
     
     pub fn display_charset(&self) {
@@ -103,4 +233,18 @@ impl Asc100Version {
         }
         println!("  ...");
     }
-}
\ No newline at end of file
+}
+
+/// Leak each distinct runtime version name once, so versions stay `Copy`
+fn intern_name(name: String) -> &'static str {
+    static NAMES: std::sync::OnceLock<std::sync::Mutex<std::collections::HashSet<&'static str>>> = std::sync::OnceLock::new();
+    let mut names = NAMES.get_or_init(Default::default).lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    match names.get(name.as_str()) {
+        Some(&interned) => interned,
+        None => {
+            let interned: &'static str = Box::leak(name.into_boxed_str());
+            names.insert(interned);
+            interned
+        }
+    }
+}
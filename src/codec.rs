@@ -0,0 +1,255 @@
+//! Unified codec owning its version, strategy and output options
+//!
+//! ```
+//! use asc100::codec::Codec;
+//! use asc100::char::extensions::ExtensionsStrategy;
+//! use asc100::char::versions::V4_URL;
+//!
+//! let codec = Codec::builder()
+//!     .version(V4_URL)
+//!     .strategy(ExtensionsStrategy::strict())
+//!     .wrap(76)
+//!     .canonical(true)
+//!     .build()
+//!     .unwrap();
+//!
+//! let encoded = codec.encode("https://example.com/#V#").unwrap();
+//! assert_eq!(codec.decode(&encoded).unwrap(), "https://example.com/#V#");
+//! ```
+
+use std::sync::Arc;
+
+use crate::char::extensions::{CoreStrategy, EncodingStrategy, StrictFilter};
+use crate::char::versions::{Asc100Version, V1_STANDARD};
+use crate::char::{BASE64_CHARS, BASE64_LOOKUP};
+use crate::{encode_to_indices, pack_with, render_indices, shift, unpack_indices, varlen, Asc100Error};
+
+/// Strategy used when none is configured, shared so default codecs allocate nothing
+static DEFAULT_STRATEGY: CoreStrategy<StrictFilter> = CoreStrategy { filter: StrictFilter };
+
+/// Characters other formats use around payloads, so never payload characters
+const RESERVED_CHARS: [char; 4] = ['#', ':', ';', '='];
+
+/// Encoder/decoder with a fixed configuration, cheap to clone and share
+///
+/// Every other entry point (`Asc100Version::encode_with`, `encode_with_strategy`,
+/// ...) is a thin wrapper over a codec.
+#[derive(Clone)]
+pub struct Codec {
+    version: Asc100Version,
+    /// `None` means the strict core strategy
+    strategy: Option<Arc<dyn EncodingStrategy + Send + Sync>>,
+    alphabet: [char; 64],
+    alphabet_lookup: [u8; 128],
+    wrap: Option<usize>,
+    canonical: bool,
+}
+
+impl Default for Codec {
+    /// `V1_STANDARD` with the strict core strategy
+    fn default() -> Self {
+        Self::new(V1_STANDARD)
+    }
+}
+
+impl Codec {
+    pub fn builder() -> CodecBuilder {
+        CodecBuilder::default()
+    }
+
+    /// Codec for a version with the strict core strategy and default options
+    pub fn new(version: Asc100Version) -> Self {
+        Self {
+            version,
+            strategy: None,
+            alphabet: BASE64_CHARS,
+            alphabet_lookup: BASE64_LOOKUP,
+            wrap: None,
+            canonical: false,
+        }
+    }
+
+    pub fn version(&self) -> &Asc100Version {
+        &self.version
+    }
+
+    pub fn wrap_width(&self) -> Option<usize> {
+        self.wrap
+    }
+
+    pub fn is_canonical(&self) -> bool {
+        self.canonical
+    }
+
+    pub fn encode(&self, input: &str) -> Result<String, Asc100Error> {
+        let mut result = String::new();
+        self.encode_into(input, &mut result)?;
+        Ok(result)
+    }
+
+    /// Append the encoded payload to `out`
+    pub fn encode_into(&self, input: &str, out: &mut String) -> Result<(), Asc100Error> {
+        self.encode_into_with(input, self.strategy(), out)
+    }
+
+    /// Decode a payload; line breaks from wrapping are ignored
+    pub fn decode(&self, encoded: &str) -> Result<String, Asc100Error> {
+        self.decode_with(encoded, self.strategy())
+    }
+
+    fn strategy(&self) -> &(dyn EncodingStrategy + Send + Sync) {
+        match &self.strategy {
+            Some(strategy) => &**strategy,
+            None => &DEFAULT_STRATEGY,
+        }
+    }
+
+    /// Encode with this codec's options but a borrowed strategy
+    pub(crate) fn encode_with<S: EncodingStrategy + ?Sized>(&self, input: &str, strategy: &S) -> Result<String, Asc100Error> {
+        let mut result = String::new();
+        self.encode_into_with(input, strategy, &mut result)?;
+        Ok(result)
+    }
+
+    fn encode_into_with<S: EncodingStrategy + ?Sized>(&self, input: &str, strategy: &S, out: &mut String) -> Result<(), Asc100Error> {
+        let indices = encode_to_indices(input, &self.version.lookup, strategy)?;
+        let packed = pack_with(&indices, self.version.packing, &self.version.charset, &self.alphabet);
+
+        match self.wrap {
+            Some(width) => {
+                // The payload alphabet is ASCII, so byte chunks are character chunks
+                for (i, line) in packed.as_bytes().chunks(width).enumerate() {
+                    if i > 0 {
+                        out.push('\n');
+                    }
+                    out.push_str(std::str::from_utf8(line).unwrap_or(""));
+                }
+            }
+            None => out.push_str(&packed),
+        }
+        Ok(())
+    }
+
+    /// Decode with this codec's options but a borrowed strategy
+    pub(crate) fn decode_with<S: EncodingStrategy + ?Sized>(&self, encoded: &str, strategy: &S) -> Result<String, Asc100Error> {
+        let indices = strategy.decode_indices(self.unpack(encoded)?)?;
+        render_indices(&indices, &self.version.charset, strategy)
+    }
+
+    /// Unpack a payload into the index stream strategies decode, ignoring
+    /// line breaks and applying the canonical check
+    pub(crate) fn unpack(&self, encoded: &str) -> Result<Vec<u8>, Asc100Error> {
+        let payload: String = encoded.chars().filter(|ch| !matches!(ch, '\n' | '\r')).collect();
+        let indices = unpack_indices(&payload, &self.version.charset, &self.alphabet_lookup)?;
+        if self.canonical {
+            self.check_canonical(&payload)?;
+        }
+        Ok(indices)
+    }
+
+    /// Reject payloads with non-zero padding bits or a trailing character
+    /// that carries no index
+    fn check_canonical(&self, payload: &str) -> Result<(), Asc100Error> {
+        match padding_issue(payload, &self.alphabet_lookup) {
+            Some(reason) => Err(Asc100Error::NonCanonicalPayload(reason.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Why a payload is not exactly what an encoder would produce, if it isn't:
+/// non-zero padding bits or a trailing character that carries no index
+///
+/// Every character must already be known to be in the alphabet.
+pub(crate) fn padding_issue(payload: &str, alphabet_lookup: &[u8; 128]) -> Option<&'static str> {
+    if let Some(body) = payload.strip_prefix(varlen::VARIABLE_TAG) {
+        return varlen::padding_issue(body, alphabet_lookup);
+    }
+    if let Some(body) = payload.strip_prefix(shift::SHIFT_TAG) {
+        return shift::padding_issue(body, alphabet_lookup);
+    }
+    let padding = (payload.len() * 6) % 7;
+    if padding >= 6 {
+        return Some("trailing character carries no index");
+    }
+
+    let last = payload.bytes().last().map_or(0, |byte| alphabet_lookup[(byte & 0x7f) as usize]);
+    if padding > 0 && last & ((1 << padding) - 1) != 0 {
+        return Some("padding bits are not zero");
+    }
+    None
+}
+
+/// Builder for [`Codec`]
+#[derive(Clone, Default)]
+pub struct CodecBuilder {
+    codec: Codec,
+    alphabet: Option<String>,
+}
+
+impl CodecBuilder {
+    pub fn version(mut self, version: Asc100Version) -> Self {
+        self.codec.version = version;
+        self
+    }
+
+    pub fn strategy<S: EncodingStrategy + Send + Sync + 'static>(mut self, strategy: S) -> Self {
+        self.codec.strategy = Some(Arc::new(strategy));
+        self
+    }
+
+    /// Replace the 64-character payload alphabet (e.g. a URL-safe variant)
+    pub fn alphabet(mut self, alphabet: &str) -> Self {
+        self.alphabet = Some(alphabet.to_string());
+        self
+    }
+
+    /// Break the payload into lines of at most `width` characters (0 disables)
+    pub fn wrap(mut self, width: usize) -> Self {
+        self.codec.wrap = (width > 0).then_some(width);
+        self
+    }
+
+    /// Only accept payloads exactly as this codec would produce them
+    pub fn canonical(mut self, canonical: bool) -> Self {
+        self.codec.canonical = canonical;
+        self
+    }
+
+    pub fn build(self) -> Result<Codec, Asc100Error> {
+        let mut codec = self.codec;
+        if let Some(alphabet) = self.alphabet {
+            (codec.alphabet, codec.alphabet_lookup) = parse_alphabet(&alphabet)?;
+        }
+        Ok(codec)
+    }
+}
+
+/// Validate a payload alphabet: 64 distinct printable ASCII characters, none of
+/// them a format tag or a character markers, parts or records use as a delimiter
+fn parse_alphabet(alphabet: &str) -> Result<([char; 64], [u8; 128]), Asc100Error> {
+    let chars: Vec<char> = alphabet.chars().collect();
+    if chars.len() != 64 {
+        return Err(Asc100Error::InvalidAlphabet(format!("expected 64 characters, found {}", chars.len())));
+    }
+
+    let mut table = ['\0'; 64];
+    let mut lookup = [255u8; 128];
+    for (i, &ch) in chars.iter().enumerate() {
+        if !ch.is_ascii_graphic() {
+            return Err(Asc100Error::InvalidAlphabet(format!("{:?} is not printable ASCII", ch)));
+        }
+        if ch == varlen::VARIABLE_TAG || ch == shift::SHIFT_TAG {
+            return Err(Asc100Error::InvalidAlphabet(format!("{:?} is reserved as a format tag", ch)));
+        }
+        if RESERVED_CHARS.contains(&ch) {
+            return Err(Asc100Error::InvalidAlphabet(format!("{:?} is reserved as a delimiter", ch)));
+        }
+        if lookup[ch as usize] != 255 {
+            return Err(Asc100Error::InvalidAlphabet(format!("{:?} appears more than once", ch)));
+        }
+        table[i] = ch;
+        lookup[ch as usize] = i as u8;
+    }
+    Ok((table, lookup))
+}
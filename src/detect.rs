@@ -0,0 +1,175 @@
+//! Heuristic version/strategy detection for untagged payloads
+//!
+//! Each candidate version and strategy trial-decodes the payload, and the
+//! result is scored on printable ratio, common characters, letter/digit
+//! adjacency, common words, letter bigrams, marker validity and padding
+//! canonicality. Scores are a plausibility ranking, not a probability.
+
+use crate::char::charset::marker_name;
+use crate::char::registry::{strategy_by_name, VersionRegistry};
+use crate::char::versions::{Asc100Version, Packing};
+use crate::char::{BASE64_CHARS, BASE64_LOOKUP};
+use crate::proto::{tokenize, validate_markers, Severity, Token};
+use crate::{pack_with, shift, unpack_indices, varlen};
+
+/// Strategies tried by `detect`
+pub const DEFAULT_STRATEGIES: &[&str] = &["core", "extensions"];
+
+const COMMON_WORDS: &[&str] = &[
+    "the", "and", "of", "to", "in", "is", "you", "that", "it", "he", "was", "for", "on", "are", "as",
+    "with", "his", "they", "at", "be", "this", "have", "from", "or", "one", "had", "by", "but", "not",
+    "what", "all", "were", "we", "when", "your", "can", "said", "there", "an", "each", "which", "she",
+    "do", "how", "their", "if", "will", "up", "other", "about", "out", "many", "then", "them", "so",
+    "some", "her", "would", "make", "like", "into", "time", "has", "no", "way", "could", "my", "than",
+    "first", "been", "who", "its", "now", "day", "get", "may", "new", "hello", "world", "test", "data",
+    "value", "name", "id", "user", "file", "path", "http", "https", "www", "com", "org", "html", "api",
+];
+
+const COMMON_BIGRAMS: &[&str] = &[
+    "th", "he", "in", "er", "an", "re", "on", "at", "en", "nd", "ti", "es", "or", "te", "of", "ed",
+    "is", "it", "al", "ar", "st", "to", "nt", "ng", "se", "ha", "as", "ou", "io", "le", "ve", "co",
+    "me", "de", "hi", "ri", "ro", "ic", "ne", "ea", "ra", "ce", "li", "ch", "ll", "be", "ma", "si",
+    "om", "ur", "ca", "el", "ta", "la", "ns", "di", "fo", "ho", "pe", "ec", "pr", "no", "ct", "us",
+    "ac", "ot", "il", "tr", "ly", "nc", "et", "ut", "ss", "so", "rs", "un", "lo", "wa", "ge", "ie",
+    "wh", "ee", "wi", "em", "ad", "ol", "rt", "po", "we", "na", "ul", "ni", "ts", "mo", "ow", "pa",
+];
+
+/// One ranked guess
+#[derive(Debug, Clone)]
+pub struct Detection {
+    /// Candidate version, with the packing the payload uses
+    pub version: Asc100Version,
+    pub strategy: String,
+    /// Plausibility from 0.0 to 1.0
+    pub confidence: f64,
+    pub decoded: String,
+}
+
+/// Rank every registered version with the default strategies, best first
+pub fn detect(encoded: &str) -> Vec<Detection> {
+    let versions = VersionRegistry::global().read().unwrap_or_else(|e| e.into_inner()).versions().to_vec();
+    detect_with(encoded, &versions, DEFAULT_STRATEGIES)
+}
+
+/// Rank the given versions and strategy names, best first
+///
+/// Combinations that fail to decode are left out. Ties keep the order of
+/// `versions` and then `strategies`.
+pub fn detect_with(encoded: &str, versions: &[Asc100Version], strategies: &[&str]) -> Vec<Detection> {
+    let packing = match encoded.chars().next() {
+        Some(varlen::VARIABLE_TAG) => Packing::Variable,
+        Some(shift::SHIFT_TAG) => Packing::Shift,
+        _ => Packing::Fixed,
+    };
+
+    let mut detections = Vec::new();
+    for version in versions {
+        let version = Asc100Version { packing, ..*version };
+        // Shift codes map to indices through the charset, so each candidate unpacks its own
+        let Ok(indices) = unpack_indices(encoded, &version.charset, &BASE64_LOOKUP) else { continue };
+        let padding = padding_score(encoded, &indices, &version);
+        let unnamed_markers = indices.iter().filter(|&&index| index >= 100 && marker_name(index).is_none()).count();
+
+        for &name in strategies {
+            let Ok(strategy) = strategy_by_name(name) else { continue };
+            let Ok(decoded) = version.decode_with(encoded, &strategy) else { continue };
+
+            let confidence = score(&decoded, unnamed_markers, padding);
+            detections.push(Detection { version, strategy: name.to_string(), confidence, decoded });
+        }
+    }
+
+    // Stable sort keeps candidate order among equal scores
+    detections.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    detections
+}
+
+fn score(decoded: &str, unnamed_markers: usize, padding: f64) -> f64 {
+    let tokens = tokenize(decoded);
+    let text: String = tokens.iter()
+        .filter_map(|token| match token {
+            Token::Text(text) => Some(text.as_str()),
+            Token::Marker(_) => None,
+        })
+        .collect();
+
+    0.15 * printable_ratio(&text)
+        + 0.15 * common_char_ratio(&text)
+        + 0.15 * transition_score(&text)
+        + 0.15 * word_score(&text)
+        + 0.15 * bigram_score(&text)
+        + 0.15 * marker_score(&tokens, unnamed_markers)
+        + 0.10 * padding
+}
+
+fn ratio(matching: usize, total: usize) -> f64 {
+    if total == 0 { 0.5 } else { matching as f64 / total as f64 }
+}
+
+fn printable_ratio(text: &str) -> f64 {
+    let printable = text.chars().filter(|&ch| ch.is_ascii_graphic() || matches!(ch, ' ' | '\t' | '\n' | '\r')).count();
+    ratio(printable, text.chars().count())
+}
+
+fn common_char_ratio(text: &str) -> f64 {
+    let common = text.chars()
+        .filter(|&ch| ch.is_ascii_alphanumeric() || matches!(ch, ' ' | '\n' | '.' | ',' | '\'' | '-' | '/' | ':'))
+        .count();
+    ratio(common, text.chars().count())
+}
+
+/// Penalize letters directly touching digits or rare symbols ("The0quick")
+fn transition_score(text: &str) -> f64 {
+    let rare = |ch: char| ch.is_ascii_digit() || matches!(ch, '~' | '^' | '`' | '|' | '{' | '}' | '\\' | '$');
+    let chars: Vec<char> = text.chars().collect();
+    let odd = chars.windows(2)
+        .filter(|pair| {
+            let (a, b) = (pair[0], pair[1]);
+            (a.is_ascii_alphabetic() && rare(b)) || (rare(a) && b.is_ascii_alphabetic())
+        })
+        .count();
+    (1.0 - 4.0 * ratio(odd, chars.len().saturating_sub(1))).max(0.0)
+}
+
+fn word_score(text: &str) -> f64 {
+    let words: Vec<String> = text.split(|ch: char| !ch.is_ascii_alphabetic())
+        .filter(|word| word.len() >= 2)
+        .map(str::to_ascii_lowercase)
+        .collect();
+    let known = words.iter().filter(|word| COMMON_WORDS.contains(&word.as_str())).count();
+    ratio(known, words.len())
+}
+
+fn bigram_score(text: &str) -> f64 {
+    let letters: Vec<char> = text.chars().map(|ch| ch.to_ascii_lowercase()).collect();
+    let mut total = 0;
+    let mut common = 0;
+    for pair in letters.windows(2) {
+        if pair.iter().all(char::is_ascii_lowercase) {
+            total += 1;
+            let bigram: String = pair.iter().collect();
+            if COMMON_BIGRAMS.contains(&bigram.as_str()) {
+                common += 1;
+            }
+        }
+    }
+    if total == 0 {
+        return 0.5;
+    }
+    // Natural text has roughly half its bigrams in the common list
+    (ratio(common, total) * 2.0).min(1.0)
+}
+
+fn marker_score(tokens: &[Token], unnamed_markers: usize) -> f64 {
+    let markers = tokens.iter().filter(|token| matches!(token, Token::Marker(_))).count() + unnamed_markers;
+    if markers == 0 {
+        return 1.0;
+    }
+    let errors = validate_markers(tokens).iter().filter(|issue| issue.severity == Severity::Error).count();
+    1.0 - ratio(errors + unnamed_markers, markers).min(1.0)
+}
+
+/// 1.0 when the payload is exactly what the candidate's encoder would produce
+fn padding_score(encoded: &str, indices: &[u8], version: &Asc100Version) -> f64 {
+    if pack_with(indices, version.packing, &version.charset, &BASE64_CHARS) == encoded { 1.0 } else { 0.0 }
+}
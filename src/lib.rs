@@ -1,7 +1,21 @@
 pub mod char;
 
+pub mod codec;
+
+pub mod transcode;
+
+pub mod detect;
+
 pub mod metrics;
 
+pub mod proto;
+
+pub mod varlen;
+
+pub mod shift;
+
+pub mod optimize;
+
 #[cfg(feature = "random")]
 pub mod rand;
 
@@ -11,8 +25,10 @@ pub mod xstream_simple;
 #[cfg(feature = "xstream")]
 pub mod xstream_transformer;
 
-use char::{BASE64_CHARS, BASE64_LOOKUP, preprocess_markers, postprocess_markers, MARKERS};
+use char::{BASE64_CHARS, BASE64_LOOKUP, preprocess_markers, postprocess_markers, marker_name, scan_markers};
 use char::extensions::EncodingStrategy;
+use char::versions::{Asc100Version, Packing};
+use codec::Codec;
 
 // Sentinel-based representation for two-phase encoding
 #[derive(Debug, Clone)]
@@ -63,6 +79,40 @@ pub enum Asc100Error {
     InvalidBase64Character(char),
     InvalidIndex(u8),
     NonAsciiInput,
+    InvalidVersionStamp { expected: u8, found: Option<u8> },
+    RejectedMarker { marker: u8, position: usize },
+    MalformedMarkers(proto::MarkerIssue),
+    MissingVariable(String),
+    MalformedCall(String),
+    UnknownFunction(String),
+    FunctionFailed { name: String, message: String },
+    MislabeledTrust { position: usize, reason: String },
+    UntrustedContent { trust: proto::Trust, position: usize },
+    MalformedEnvelope(String),
+    HandshakeFailed(String),
+    CorruptFrame { offset: usize, reason: String },
+    Io(String),
+    InvalidPart(String),
+    DuplicatePart { message_id: String, index: usize },
+    ChunkBudgetTooSmall(usize),
+    TooManyParts(usize),
+    TooManyPendingMessages(usize),
+    InvalidMessageId(String),
+    MalformedRecords(String),
+    MissingNewlineFlag,
+    TrailingContent { position: usize },
+    MissingEof,
+    UnknownStrategy(String),
+    InvalidAlphabet(String),
+    NonCanonicalPayload(String),
+    InvalidCharset { duplicates: Vec<char>, missing: Vec<char>, unexpected: Vec<char> },
+    InvalidControlMarker(u8),
+    DuplicateVersion(String),
+    UnknownVersion(String),
+    InvalidVersionTag(String),
+    MalformedDigitRun(String),
+    InvalidDictionary(String),
+    UnknownDictionary(u8),
 }
 
 impl std::fmt::Display for Asc100Error {
@@ -89,6 +139,48 @@ impl std::fmt::Display for Asc100Error {
             Asc100Error::InvalidBase64Character(c) => write!(f, "Invalid base64 character: '{}'", c),
             Asc100Error::InvalidIndex(i) => write!(f, "Invalid index: {}", i),
             Asc100Error::NonAsciiInput => write!(f, "Input contains non-ASCII characters"),
+            Asc100Error::InvalidVersionStamp { expected, found } => match found {
+                Some(found) => write!(f, "Version stamp mismatch: expected control marker {}, found {}", expected, found),
+                None => write!(f, "Missing version stamp: expected control marker {}", expected),
+            },
+            Asc100Error::RejectedMarker { marker, position } => write!(f, "Marker {} not allowed at position {}", 
+                char::marker_name(*marker).map_or_else(|| format!("index {}", marker), str::to_string),
+                position
+            ),
+            Asc100Error::MalformedMarkers(issue) => write!(f, "Malformed markers: {}", issue),
+            Asc100Error::MissingVariable(name) => write!(f, "Missing template variable: {}", name),
+            Asc100Error::MalformedCall(reason) => write!(f, "Malformed function call: {}", reason),
+            Asc100Error::UnknownFunction(name) => write!(f, "Unknown function: {}", name),
+            Asc100Error::FunctionFailed { name, message } => write!(f, "Function {} failed: {}", name, message),
+            Asc100Error::MislabeledTrust { position, reason } => write!(f, "Mislabeled trust zone at position {}: {}", position, reason),
+            Asc100Error::UntrustedContent { trust, position } => write!(f, "{:?} content refused at position {}", trust, position),
+            Asc100Error::MalformedEnvelope(reason) => write!(f, "Malformed envelope: {}", reason),
+            Asc100Error::HandshakeFailed(reason) => write!(f, "Handshake failed: {}", reason),
+            Asc100Error::CorruptFrame { offset, reason } => write!(f, "Corrupt frame at offset {}: {}", offset, reason),
+            Asc100Error::Io(message) => write!(f, "I/O error: {}", message),
+            Asc100Error::InvalidPart(part) => write!(f, "Invalid message part: {}", part),
+            Asc100Error::DuplicatePart { message_id, index } => write!(f, "Duplicate part {} of message {}", index, message_id),
+            Asc100Error::ChunkBudgetTooSmall(budget) => write!(f, "Part budget of {} characters cannot fit a part header", budget),
+            Asc100Error::TooManyParts(total) => write!(f, "Message needs {} parts, at most {} allowed", total, proto::MAX_PARTS),
+            Asc100Error::TooManyPendingMessages(limit) => write!(f, "Already reassembling {} messages", limit),
+            Asc100Error::InvalidMessageId(id) => write!(f, "Invalid message ID {:?}: must be non-empty without ':'", id),
+            Asc100Error::MalformedRecords(reason) => write!(f, "Malformed records: {}", reason),
+            Asc100Error::MissingNewlineFlag => write!(f, "Payload does not record its original line ending"),
+            Asc100Error::TrailingContent { position } => write!(f, "Content after #EOF# at position {}", position),
+            Asc100Error::MissingEof => write!(f, "Payload has no #EOF# marker, it may be truncated"),
+            Asc100Error::UnknownStrategy(name) => write!(f, "Unknown strategy: {}", name),
+            Asc100Error::InvalidAlphabet(reason) => write!(f, "Invalid payload alphabet: {}", reason),
+            Asc100Error::NonCanonicalPayload(reason) => write!(f, "Non-canonical payload: {}", reason),
+            Asc100Error::InvalidCharset { duplicates, missing, unexpected } => write!(
+                f, "Invalid charset: duplicates {:?}, missing {:?}, unexpected {:?}", duplicates, missing, unexpected
+            ),
+            Asc100Error::InvalidControlMarker(index) => write!(f, "Marker index {} is not free for version stamps", index),
+            Asc100Error::DuplicateVersion(name) => write!(f, "A different version is already registered as {}", name),
+            Asc100Error::UnknownVersion(id) => write!(f, "Unknown version: {}", id),
+            Asc100Error::InvalidVersionTag(tag) => write!(f, "Invalid version tag: {}", tag),
+            Asc100Error::MalformedDigitRun(reason) => write!(f, "Malformed digit run: {}", reason),
+            Asc100Error::InvalidDictionary(reason) => write!(f, "Invalid dictionary: {}", reason),
+            Asc100Error::UnknownDictionary(id) => write!(f, "Unknown dictionary id: {}", id),
         }
     }
 }
@@ -100,47 +192,27 @@ impl std::error::Error for Asc100Error {}
 // ============================================================================
 
 /// Parse input into sentinels, separating text from markers
-fn parse_sentinels<S: EncodingStrategy>(input: &str, strategy: &S) -> Result<Vec<Sentinel>, Asc100Error> {
+fn parse_sentinels<S: EncodingStrategy + ?Sized>(input: &str, strategy: &S) -> Result<Vec<Sentinel>, Asc100Error> {
     let mut sentinels = Vec::new();
-    let mut current_text = String::new();
-    let mut chars = input.chars().peekable();
+    let mut text_start = 0;
     
-    while let Some(ch) = chars.next() {
-        if ch == '#' {
-            // Potential marker start
-            let mut marker_candidate = String::from("#");
-            
-            // Collect characters until next #
-            while let Some(&next_ch) = chars.peek() {
-                marker_candidate.push(chars.next().unwrap());
-                if next_ch == '#' {
-                    break;
-                }
-            }
-            
-            // Check if this is a valid marker
-            if let Some((_, marker_index)) = MARKERS.iter().find(|(marker_str, _)| *marker_str == &marker_candidate) {
-                if strategy.supports_index(*marker_index) {
-                    // Valid marker - save any accumulated text first
-                    if !current_text.is_empty() {
-                        sentinels.push(Sentinel::Text(current_text.clone()));
-                        current_text.clear();
-                    }
-                    sentinels.push(Sentinel::Marker(*marker_index));
-                    continue;
-                }
-            }
-            
-            // Not a valid marker, treat as regular text
-            current_text.push_str(&marker_candidate);
-        } else {
-            current_text.push(ch);
+    for (pos, marker_index) in scan_markers(input) {
+        // Unsupported markers stay in the surrounding text
+        if !strategy.supports_index(marker_index) {
+            continue;
         }
+        
+        // Valid marker - save any accumulated text first
+        if pos > text_start {
+            sentinels.push(Sentinel::Text(input[text_start..pos].to_string()));
+        }
+        sentinels.push(Sentinel::Marker(marker_index));
+        text_start = pos + marker_name(marker_index).map_or(0, str::len);
     }
     
     // Add any remaining text
-    if !current_text.is_empty() {
-        sentinels.push(Sentinel::Text(current_text));
+    if text_start < input.len() {
+        sentinels.push(Sentinel::Text(input[text_start..].to_string()));
     }
     
     Ok(sentinels)
@@ -150,12 +222,21 @@ fn parse_sentinels<S: EncodingStrategy>(input: &str, strategy: &S) -> Result<Vec
 // STRATEGY-BASED ENCODING (NEW)
 // ============================================================================
 
-pub fn encode_with_strategy<S: EncodingStrategy>(
+pub fn encode_with_strategy<S: EncodingStrategy + ?Sized>(
     input: &str, 
-    _charset: &[char; 100], 
+    charset: &[char; 100], 
     lookup: &[u8; 128], 
     strategy: &S
 ) -> Result<String, Asc100Error> {
+    Codec::new(Asc100Version::from_tables(*charset, *lookup)).encode_with(input, strategy)
+}
+
+/// Run the strategy pipeline up to the final index stream
+pub(crate) fn encode_to_indices<S: EncodingStrategy + ?Sized>(
+    input: &str,
+    lookup: &[u8; 128],
+    strategy: &S
+) -> Result<Vec<u8>, Asc100Error> {
     // Phase 1: Apply strategy preprocessing (filtering only)
     let filtered_input = strategy.preprocess(input)?;
     
@@ -189,9 +270,15 @@ pub fn encode_with_strategy<S: EncodingStrategy>(
         }
     }
     
+    // Phase 4: Let the strategy adjust the final index stream
+    strategy.encode_indices(indices)
+}
+
+/// Pack 7-bit indices into a Base64 payload using the given alphabet
+pub(crate) fn pack_indices(indices: &[u8], alphabet: &[char; 64]) -> String {
     // Convert indices to 7-bit binary
     let mut bits = Vec::with_capacity(indices.len() * 7);
-    for index in indices {
+    for &index in indices {
         for i in (0..7).rev() {
             bits.push((index >> i) & 1);
         }
@@ -209,17 +296,47 @@ pub fn encode_with_strategy<S: EncodingStrategy>(
         for (i, &bit) in chunk.iter().enumerate() {
             value |= bit << (5 - i);
         }
-        result.push(BASE64_CHARS[value as usize]);
+        result.push(alphabet[value as usize]);
     }
     
-    Ok(result)
+    result
 }
 
-pub fn decode_with_strategy<S: EncodingStrategy>(
+pub fn decode_with_strategy<S: EncodingStrategy + ?Sized>(
     encoded: &str, 
     charset: &[char; 100], 
     strategy: &S
 ) -> Result<String, Asc100Error> {
+    let lookup = char::charset::build_lookup_table(*charset);
+    Codec::new(Asc100Version::from_tables(*charset, lookup)).decode_with(encoded, strategy)
+}
+
+/// Pack a version's indices with the given packing mode
+pub(crate) fn pack_with(indices: &[u8], packing: Packing, charset: &[char; 100], alphabet: &[char; 64]) -> String {
+    match packing {
+        Packing::Fixed => pack_indices(indices, alphabet),
+        Packing::Variable => varlen::pack(indices, alphabet),
+        Packing::Shift => shift::pack(indices, charset, alphabet),
+    }
+}
+
+/// Unpack a payload into a version's indices using the given alphabet lookup,
+/// accepting fixed-width and tagged variable-length or shift-coded payloads
+pub(crate) fn unpack_indices(
+    encoded: &str,
+    charset: &[char; 100],
+    alphabet_lookup: &[u8; 128]
+) -> Result<Vec<u8>, Asc100Error> {
+    if let Some(body) = encoded.strip_prefix(varlen::VARIABLE_TAG) {
+        return varlen::unpack(body, alphabet_lookup);
+    }
+    match encoded.strip_prefix(shift::SHIFT_TAG) {
+        Some(body) => shift::unpack(body, charset, alphabet_lookup),
+        None => unpack_fixed(encoded, alphabet_lookup),
+    }
+}
+
+pub(crate) fn unpack_fixed(encoded: &str, alphabet_lookup: &[u8; 128]) -> Result<Vec<u8>, Asc100Error> {
     // Convert base64 to binary
     let mut bits = Vec::with_capacity(encoded.len() * 6);
     
@@ -229,7 +346,7 @@ pub fn decode_with_strategy<S: EncodingStrategy>(
             return Err(Asc100Error::InvalidBase64Character(ch));
         }
         
-        let value = char::BASE64_LOOKUP[ascii as usize];
+        let value = alphabet_lookup[ascii as usize];
         if value == 255 {
             return Err(Asc100Error::InvalidBase64Character(ch));
         }
@@ -254,20 +371,28 @@ pub fn decode_with_strategy<S: EncodingStrategy>(
         }
     }
     
-    // Convert indices to characters
+    Ok(indices)
+}
+
+/// Convert indices back to text and apply strategy postprocessing
+pub(crate) fn render_indices<S: EncodingStrategy + ?Sized>(
+    indices: &[u8],
+    charset: &[char; 100],
+    strategy: &S
+) -> Result<String, Asc100Error> {
     let mut result = String::with_capacity(indices.len());
-    for index in indices {
+    for &index in indices {
         if index >= 100 && index <= 127 {
             // Extension marker - check if strategy supports it
             if !strategy.supports_index(index) {
                 return Err(Asc100Error::InvalidIndex(index));
             }
-            // Convert marker index directly to marker string
-            let marker_str = MARKERS.iter()
-                .find(|(_, marker_index)| *marker_index == index)
-                .map(|(marker_str, _)| *marker_str)
-                .unwrap_or("");
-            result.push_str(marker_str);
+            // Convert marker index directly to marker string; markers without
+            // one (version stamps, mode switches) must be consumed by the strategy
+            match marker_name(index) {
+                Some(name) => result.push_str(name),
+                None => return Err(Asc100Error::InvalidIndex(index)),
+            }
         } else if index < 100 {
             // Regular character from charset
             result.push(charset[index as usize]);
@@ -0,0 +1,212 @@
+//! Corpus-driven charset generation
+//!
+//! `CorpusStats` counts character and marker frequencies over sample text and
+//! orders the charset so the most frequent characters get the shortest
+//! variable-length codes (see [`crate::varlen`]). The result is available as a
+//! runtime `Asc100Version` and as Rust source for a `const` definition, and
+//! `report` compares its expected size against the built-in versions.
+//!
+//! ```
+//! use asc100::optimize::CorpusStats;
+//!
+//! let mut stats = CorpusStats::new();
+//! stats.add("status=ok&id=42").add("status=error&id=7");
+//!
+//! let version = stats.optimal_version("v5_query");
+//! assert_eq!(&version.charset[..3], &['=', 's', 't']);
+//! let encoded = version.encode("status=ok").unwrap();
+//! assert_eq!(version.decode(&encoded).unwrap(), "status=ok");
+//!
+//! println!("{}", stats.report(&version));
+//! ```
+
+use std::fmt;
+
+use crate::char::charset::{create_base_charset, marker_name, scan_markers};
+use crate::char::versions::{Asc100Version, Packing, V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL};
+use crate::shift::LOWER_PLANE;
+use crate::varlen::code_length;
+
+/// Character and marker frequencies over a corpus
+#[derive(Debug, Clone)]
+pub struct CorpusStats {
+    /// Counts by ASCII code
+    chars: [u64; 128],
+    /// Counts by marker index - 100
+    markers: [u64; 28],
+    unsupported: u64,
+}
+
+impl Default for CorpusStats {
+    fn default() -> Self {
+        Self { chars: [0; 128], markers: [0; 28], unsupported: 0 }
+    }
+}
+
+impl CorpusStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stats over every sample in a corpus
+    pub fn from_corpus<'a>(samples: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut stats = Self::new();
+        for sample in samples {
+            stats.add(sample);
+        }
+        stats
+    }
+
+    /// Count one sample; marker strings are counted as markers, not characters
+    pub fn add(&mut self, text: &str) -> &mut Self {
+        let mut rest = 0;
+        for (start, index) in scan_markers(text) {
+            self.add_chars(&text[rest..start]);
+            self.markers[(index - 100) as usize] += 1;
+            rest = start + marker_name(index).map_or(0, str::len);
+        }
+        self.add_chars(&text[rest..]);
+        self
+    }
+
+    fn add_chars(&mut self, text: &str) {
+        for ch in text.chars() {
+            if (ch as u32) < 128 && V1_STANDARD.lookup[ch as usize] != 255 {
+                self.chars[ch as usize] += 1;
+            } else {
+                self.unsupported += 1;
+            }
+        }
+    }
+
+    pub fn char_count(&self, ch: char) -> u64 {
+        if (ch as u32) < 128 { self.chars[ch as usize] } else { 0 }
+    }
+
+    pub fn marker_count(&self, index: u8) -> u64 {
+        if (100..128).contains(&index) { self.markers[(index - 100) as usize] } else { 0 }
+    }
+
+    /// Characters outside the charset, left out of every size estimate
+    pub fn unsupported(&self) -> u64 {
+        self.unsupported
+    }
+
+    /// Number of indices the corpus encodes to
+    pub fn total(&self) -> u64 {
+        self.chars.iter().sum::<u64>() + self.markers.iter().sum::<u64>()
+    }
+
+    /// Charset ordered by descending frequency; ties keep the base charset order
+    pub fn optimal_charset(&self) -> [char; 100] {
+        let mut charset = create_base_charset();
+        // Stable sort, so unseen characters stay in base order at the end
+        charset.sort_by_key(|&ch| std::cmp::Reverse(self.chars[ch as usize]));
+        charset
+    }
+
+    /// Variable-length version using the optimal charset
+    pub fn optimal_version(&self, name: &'static str) -> Asc100Version {
+        Asc100Version::custom(name, self.optimal_charset())
+            .expect("a reordered base charset is always valid")
+            .variable()
+    }
+
+    /// Expected payload size in bits for this corpus with the version's packing
+    ///
+    /// For shift coding this assumes every symbol outside the lower plane is
+    /// shifted, which ignores latching and so overestimates runs.
+    pub fn expected_bits(&self, version: &Asc100Version) -> u64 {
+        match version.packing {
+            Packing::Fixed => return self.total() * 7,
+            Packing::Shift => {
+                let lower: u64 = LOWER_PLANE.chars().map(|ch| self.chars[ch as usize]).sum();
+                return lower * 6 + (self.total() - lower) * 12;
+            }
+            Packing::Variable => {}
+        }
+        let chars: u64 = version.charset.iter()
+            .enumerate()
+            .map(|(index, &ch)| self.chars[ch as usize] * code_length(index as u8) as u64)
+            .sum();
+        let markers: u64 = self.markers.iter()
+            .enumerate()
+            .map(|(offset, &count)| count * code_length(100 + offset as u8) as u64)
+            .sum();
+        chars + markers
+    }
+
+    /// Compare fixed packing, each built-in version and `candidate`
+    pub fn report(&self, candidate: &Asc100Version) -> SizeReport {
+        let fixed_bits = self.total() * 7;
+        let mut rows = vec![SizeEstimate { name: "fixed".to_string(), bits: fixed_bits }];
+        for version in [V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL, *candidate] {
+            let version = version.variable();
+            rows.push(SizeEstimate { name: version.name.to_string(), bits: self.expected_bits(&version) });
+        }
+        SizeReport { indices: self.total(), unsupported: self.unsupported, fixed_bits, rows }
+    }
+}
+
+/// Expected size of the corpus under one version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeEstimate {
+    pub name: String,
+    pub bits: u64,
+}
+
+impl SizeEstimate {
+    /// Payload characters, ignoring the per-payload tag and padding
+    pub fn payload_chars(&self) -> u64 {
+        self.bits.saturating_add(5) / 6
+    }
+}
+
+/// Size comparison produced by [`CorpusStats::report`]
+///
+/// The first row is fixed packing (the same for every version); the rest use
+/// variable-length packing, with the candidate last.
+#[derive(Debug, Clone)]
+pub struct SizeReport {
+    pub indices: u64,
+    pub unsupported: u64,
+    pub fixed_bits: u64,
+    pub rows: Vec<SizeEstimate>,
+}
+
+impl SizeReport {
+    /// Row with the fewest bits; earlier rows win ties
+    pub fn best(&self) -> Option<&SizeEstimate> {
+        self.rows.iter().reduce(|best, row| if row.bits < best.bits { row } else { best })
+    }
+}
+
+impl fmt::Display for SizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} indices, {} unsupported characters", self.indices, self.unsupported)?;
+        writeln!(f, "{:<24} {:>12} {:>12} {:>8}", "version", "bits", "chars", "vs fixed")?;
+        for row in &self.rows {
+            let ratio = if self.fixed_bits == 0 { 1.0 } else { row.bits as f64 / self.fixed_bits as f64 };
+            writeln!(f, "{:<24} {:>12} {:>12} {:>7.1}%", row.name, row.bits, row.payload_chars(), ratio * 100.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// Rust source for a `const` definition of `version`, for pasting into `versions.rs`
+pub fn to_rust_source(version: &Asc100Version, const_name: &str) -> String {
+    let mut source = format!("const {}_CHARSET: [char; 100] = [", const_name);
+    for (i, ch) in version.charset.iter().enumerate() {
+        source.push_str(if i % 10 == 0 { "\n    " } else { " " });
+        source.push_str(&format!("{:?},", ch));
+    }
+    source.push_str("\n];\n\n");
+    source.push_str(&format!("pub const {}: Asc100Version = Asc100Version {{\n", const_name));
+    source.push_str(&format!("    name: {:?},\n", version.name));
+    source.push_str(&format!("    charset: {}_CHARSET,\n", const_name));
+    source.push_str(&format!("    lookup: build_lookup_table({}_CHARSET),\n", const_name));
+    source.push_str(&format!("    control_marker: {},\n", version.control_marker));
+    source.push_str(&format!("    packing: Packing::{:?},\n", version.packing));
+    source.push_str("};\n");
+    source
+}
@@ -0,0 +1,166 @@
+//! Function call payloads using `#FX#` / `#ARG#`
+//!
+//! A call serializes as `#FX#name#ARG#a#ARG#b`. Literal `#` and `"` inside the
+//! name or arguments are written as `#E#` and `#Q#`, so argument text never
+//! contains raw marker characters and nested calls survive as plain arguments.
+
+use std::collections::HashMap;
+
+use super::tokens::{Token, tokenize, escape_text, push_unescaped};
+use crate::char::charset::{MARKER_ARG, MARKER_FX};
+use crate::char::extensions::ExtensionsStrategy;
+use crate::char::versions::Asc100Version;
+use crate::Asc100Error;
+
+/// A single `#FX#` invocation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionCall {
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+impl FunctionCall {
+    pub fn new(name: &str, args: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    /// Parse text holding exactly one call
+    pub fn parse(text: &str) -> Result<Self, Asc100Error> {
+        let mut calls = parse_calls(text)?;
+        if calls.len() != 1 {
+            return Err(Asc100Error::MalformedCall(format!("expected one call, found {}", calls.len())));
+        }
+        Ok(calls.remove(0))
+    }
+
+    /// Decode an ASC100 payload holding exactly one call
+    pub fn from_encoded(encoded: &str, version: &Asc100Version) -> Result<Self, Asc100Error> {
+        Self::parse(&version.decode_with(encoded, &ExtensionsStrategy::strict())?)
+    }
+
+    /// Encode this call as an ASC100 payload
+    pub fn encode(&self, version: &Asc100Version) -> Result<String, Asc100Error> {
+        version.encode_with(&self.to_string(), &ExtensionsStrategy::strict())
+    }
+}
+
+impl std::fmt::Display for FunctionCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#FX#{}", escape_text(&self.name))?;
+        for arg in &self.args {
+            write!(f, "#ARG#{}", escape_text(arg))?;
+        }
+        Ok(())
+    }
+}
+
+/// Field of the call currently being filled
+enum Field {
+    Name(String),
+    Arg(String),
+}
+
+impl Field {
+    fn text(&mut self) -> &mut String {
+        match self {
+            Field::Name(text) | Field::Arg(text) => text,
+        }
+    }
+}
+
+/// Parse every call in text; each `#FX#` starts a new call
+pub fn parse_calls(text: &str) -> Result<Vec<FunctionCall>, Asc100Error> {
+    let mut calls: Vec<FunctionCall> = Vec::new();
+    let mut field: Option<Field> = None;
+
+    for token in tokenize(text) {
+        match token {
+            Token::Marker(MARKER_FX) => {
+                if let Some(call) = calls.last_mut() {
+                    finish_field(call, field.take());
+                }
+                calls.push(FunctionCall { name: String::new(), args: Vec::new() });
+                field = Some(Field::Name(String::new()));
+            }
+            Token::Marker(MARKER_ARG) => {
+                let call = calls.last_mut()
+                    .ok_or_else(|| Asc100Error::MalformedCall("#ARG# before #FX#".to_string()))?;
+                finish_field(call, field.take());
+                field = Some(Field::Arg(String::new()));
+            }
+            other => {
+                let current = field.as_mut()
+                    .ok_or_else(|| Asc100Error::MalformedCall("content before #FX#".to_string()))?
+                    .text();
+                push_unescaped(current, &other);
+            }
+        }
+    }
+
+    if let Some(call) = calls.last_mut() {
+        finish_field(call, field.take());
+    }
+
+    if calls.iter().any(|call| call.name.is_empty()) {
+        return Err(Asc100Error::MalformedCall("#FX# without a function name".to_string()));
+    }
+    Ok(calls)
+}
+
+fn finish_field(call: &mut FunctionCall, field: Option<Field>) {
+    match field {
+        Some(Field::Name(name)) => call.name = name,
+        Some(Field::Arg(arg)) => call.args.push(arg),
+        None => {}
+    }
+}
+
+type Handler = Box<dyn Fn(&[String]) -> Result<String, String> + Send + Sync>;
+
+/// Maps decoded calls to registered Rust closures
+#[derive(Default)]
+pub struct Dispatcher {
+    handlers: HashMap<String, Handler>,
+}
+
+impl Dispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a handler, replacing any previous one with the same name
+    pub fn register<F>(&mut self, name: &str, handler: F) -> &mut Self
+    where
+        F: Fn(&[String]) -> Result<String, String> + Send + Sync + 'static,
+    {
+        self.handlers.insert(name.to_string(), Box::new(handler));
+        self
+    }
+
+    pub fn is_registered(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+
+    /// Run a single call
+    pub fn dispatch(&self, call: &FunctionCall) -> Result<String, Asc100Error> {
+        let handler = self.handlers.get(&call.name)
+            .ok_or_else(|| Asc100Error::UnknownFunction(call.name.clone()))?;
+
+        handler(&call.args).map_err(|message| Asc100Error::FunctionFailed {
+            name: call.name.clone(),
+            message,
+        })
+    }
+
+    /// Decode an ASC100 payload and run every call in it, in order
+    pub fn dispatch_encoded(&self, encoded: &str, version: &Asc100Version) -> Result<Vec<String>, Asc100Error> {
+        let text = version.decode_with(encoded, &ExtensionsStrategy::strict())?;
+        parse_calls(&text)?
+            .iter()
+            .map(|call| self.dispatch(call))
+            .collect()
+    }
+}
@@ -0,0 +1,235 @@
+//! Chunked multi-part messages
+//!
+//! A message is ASC100-encoded once and the encoded text is split into parts
+//! that each fit a size budget. Every part is written as `id:index:total:payload`
+//! (index is 1-based); `:` never appears in the Base64 payload alphabet.
+//!
+//! A message has at most [`MAX_PARTS`] parts, and a [`Reassembler`] holds at
+//! most a fixed number of incomplete messages, so untrusted parts cannot make
+//! it allocate without bound.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::char::extensions::{EncodingStrategy, ExtensionsStrategy};
+use crate::char::versions::Asc100Version;
+use crate::Asc100Error;
+
+/// Most parts a message can be split into
+pub const MAX_PARTS: usize = 10_000;
+
+/// Incomplete messages a reassembler holds by default
+pub const DEFAULT_MAX_PENDING: usize = 64;
+
+/// One numbered part of a chunked message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Part {
+    pub message_id: String,
+    pub index: usize,
+    pub total: usize,
+    pub payload: String,
+}
+
+impl Part {
+    /// Parse `id:index:total:payload`
+    pub fn parse(text: &str) -> Result<Self, Asc100Error> {
+        let invalid = || Asc100Error::InvalidPart(text.to_string());
+
+        let mut fields = text.splitn(4, ':');
+        let message_id = fields.next().filter(|id| !id.is_empty()).ok_or_else(invalid)?;
+        let index: usize = fields.next().and_then(|v| v.parse().ok()).ok_or_else(invalid)?;
+        let total: usize = fields.next().and_then(|v| v.parse().ok()).ok_or_else(invalid)?;
+        let payload = fields.next().ok_or_else(invalid)?;
+
+        let part = Self { message_id: message_id.to_string(), index, total, payload: payload.to_string() };
+        if !part.is_valid() {
+            return Err(invalid());
+        }
+        Ok(part)
+    }
+
+    /// Index within `1..=total`, total within [`MAX_PARTS`], and an ID without `:`
+    fn is_valid(&self) -> bool {
+        self.index >= 1 && self.index <= self.total && self.total <= MAX_PARTS && check_message_id(&self.message_id).is_ok()
+    }
+}
+
+impl std::fmt::Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}:{}", self.message_id, self.index, self.total, self.payload)
+    }
+}
+
+/// Splits messages into parts no longer than a character budget
+#[derive(Clone)]
+pub struct Splitter {
+    version: Asc100Version,
+    budget: usize,
+    message_id: Option<String>,
+}
+
+impl Splitter {
+    pub fn new(version: &Asc100Version, budget: usize) -> Self {
+        Self { version: *version, budget, message_id: None }
+    }
+
+    /// Use a fixed message ID instead of one derived from the content; the ID
+    /// must be non-empty and must not contain `:`
+    pub fn with_message_id(mut self, message_id: &str) -> Result<Self, Asc100Error> {
+        check_message_id(message_id)?;
+        self.message_id = Some(message_id.to_string());
+        Ok(self)
+    }
+
+    /// Split using the extensions strategy
+    pub fn split(&self, input: &str) -> Result<Vec<Part>, Asc100Error> {
+        self.split_with(input, &ExtensionsStrategy::strict())
+    }
+
+    pub fn split_with<S: EncodingStrategy>(&self, input: &str, strategy: &S) -> Result<Vec<Part>, Asc100Error> {
+        let encoded = self.version.encode_with(input, strategy)?;
+        let message_id = self.message_id.clone().unwrap_or_else(|| content_id(input));
+
+        // The header grows with the digit count of `total`, so settle it first
+        let mut total = 1;
+        let chunk_len = loop {
+            let header_len = message_id.len() + 2 * digits(total) + 3;
+            if self.budget <= header_len {
+                return Err(Asc100Error::ChunkBudgetTooSmall(self.budget));
+            }
+            let chunk_len = self.budget - header_len;
+            let needed = encoded.len().div_ceil(chunk_len).max(1);
+            if needed <= total {
+                break chunk_len;
+            }
+            total = needed;
+        };
+
+        let chunks: Vec<&str> = if encoded.is_empty() {
+            vec![""]
+        } else {
+            // Base64 output is ASCII, so byte chunks are character chunks
+            encoded.as_bytes().chunks(chunk_len).map(|c| std::str::from_utf8(c).unwrap_or("")).collect()
+        };
+
+        let total = chunks.len();
+        if total > MAX_PARTS {
+            return Err(Asc100Error::TooManyParts(total));
+        }
+        Ok(chunks.into_iter().enumerate().map(|(i, payload)| Part {
+            message_id: message_id.clone(),
+            index: i + 1,
+            total,
+            payload: payload.to_string(),
+        }).collect())
+    }
+}
+
+/// Collects parts in any order and yields each message once it is complete
+#[derive(Clone)]
+pub struct Reassembler {
+    version: Asc100Version,
+    pending: HashMap<String, (usize, BTreeMap<usize, String>)>,
+    max_pending: usize,
+}
+
+impl Reassembler {
+    pub fn new(version: &Asc100Version) -> Self {
+        Self { version: *version, pending: HashMap::new(), max_pending: DEFAULT_MAX_PENDING }
+    }
+
+    /// Limit the number of incomplete messages held at once
+    pub fn with_max_pending(mut self, max_pending: usize) -> Self {
+        self.max_pending = max_pending;
+        self
+    }
+
+    /// Add a part using the extensions strategy
+    pub fn push(&mut self, part: &str) -> Result<Option<String>, Asc100Error> {
+        self.push_with(Part::parse(part)?, &ExtensionsStrategy::strict())
+    }
+
+    /// Add a parsed part; returns the decoded message once every part has arrived
+    ///
+    /// If decoding the complete message fails, its parts stay pending so the
+    /// error can be inspected and the message retried with another strategy.
+    pub fn push_with<S: EncodingStrategy>(&mut self, part: Part, strategy: &S) -> Result<Option<String>, Asc100Error> {
+        if !part.is_valid() {
+            return Err(Asc100Error::InvalidPart(part.to_string()));
+        }
+        if !self.pending.contains_key(&part.message_id) && self.pending.len() >= self.max_pending {
+            return Err(Asc100Error::TooManyPendingMessages(self.max_pending));
+        }
+        let (total, parts) = self.pending
+            .entry(part.message_id.clone())
+            .or_insert_with(|| (part.total, BTreeMap::new()));
+
+        if *total != part.total {
+            return Err(Asc100Error::InvalidPart(format!(
+                "{} claims {} parts, expected {}", part.message_id, part.total, total
+            )));
+        }
+        if parts.contains_key(&part.index) {
+            return Err(Asc100Error::DuplicatePart { message_id: part.message_id, index: part.index });
+        }
+        parts.insert(part.index, part.payload);
+
+        if parts.len() < *total {
+            return Ok(None);
+        }
+
+        let encoded: String = parts.values().map(String::as_str).collect();
+        let decoded = self.version.decode_with(&encoded, strategy)?;
+        self.pending.remove(&part.message_id);
+        Ok(Some(decoded))
+    }
+
+    /// Try again to decode a complete message whose earlier decode failed
+    pub fn retry_with<S: EncodingStrategy>(&mut self, message_id: &str, strategy: &S) -> Result<Option<String>, Asc100Error> {
+        let encoded: String = match self.pending.get(message_id) {
+            Some((total, parts)) if parts.len() == *total => parts.values().map(String::as_str).collect(),
+            _ => return Ok(None),
+        };
+        let decoded = self.version.decode_with(&encoded, strategy)?;
+        self.pending.remove(message_id);
+        Ok(Some(decoded))
+    }
+
+    /// Drop the parts of a message, e.g. one that will never complete
+    pub fn discard(&mut self, message_id: &str) -> bool {
+        self.pending.remove(message_id).is_some()
+    }
+
+    /// Part indices still missing for a message being reassembled
+    pub fn missing(&self, message_id: &str) -> Vec<usize> {
+        match self.pending.get(message_id) {
+            Some((total, parts)) => (1..=*total).filter(|i| !parts.contains_key(i)).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// IDs of messages with parts received but not yet complete
+    pub fn pending_ids(&self) -> Vec<&str> {
+        self.pending.keys().map(String::as_str).collect()
+    }
+}
+
+fn check_message_id(message_id: &str) -> Result<(), Asc100Error> {
+    if message_id.is_empty() || message_id.contains(':') {
+        return Err(Asc100Error::InvalidMessageId(message_id.to_string()));
+    }
+    Ok(())
+}
+
+fn digits(mut value: usize) -> usize {
+    let mut count = 1;
+    while value >= 10 {
+        value /= 10;
+        count += 1;
+    }
+    count
+}
+
+/// Short content-derived message ID (FNV-1a, 32-bit hex)
+fn content_id(input: &str) -> String {
+    format!("{:08x}", crate::char::charset::fnv1a(input.bytes()))
+}
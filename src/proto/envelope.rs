@@ -0,0 +1,174 @@
+//! Metadata envelopes using `#MEM#` / `#CTX#`
+//!
+//! An envelope serializes as `#MEM#k=v#BRK#k=v#MEM##CTX#k=v#CTX#body`:
+//! `#MEM#` carries transmission metadata, `#CTX#` carries payload context, and
+//! everything after the header is the raw body. Keys and values escape `#` and
+//! `"` as `#E#` and `#Q#`; keys must not contain `=`.
+//!
+//! Unlike a bare `#MEM#k=v#BRK#...#CTX#...` prefix, each section is closed by
+//! repeating its marker. That keeps an empty section apart from a missing one
+//! and lets the body start with any text, including `#BRK#` or `k=v`.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use super::tokens::{Token, tokenize, escape_text, push_unescaped};
+use crate::char::charset::{MARKER_BRK, MARKER_CTX, MARKER_MEM};
+use crate::char::extensions::ExtensionsStrategy;
+use crate::char::versions::Asc100Version;
+use crate::Asc100Error;
+
+/// Well-known metadata keys
+pub const META_CONTENT_TYPE: &str = "content-type";
+pub const META_TIMESTAMP: &str = "timestamp";
+pub const META_ORIGIN: &str = "origin";
+
+/// Payload with transmission metadata and context
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Envelope {
+    pub meta: BTreeMap<String, String>,
+    pub context: BTreeMap<String, String>,
+    pub body: String,
+}
+
+impl Envelope {
+    pub fn new(body: &str) -> Self {
+        Self { body: body.to_string(), ..Self::default() }
+    }
+
+    /// Add a metadata entry; keys containing `=` are rejected
+    pub fn with_meta(mut self, key: &str, value: &str) -> Result<Self, Asc100Error> {
+        check_key(key)?;
+        self.meta.insert(key.to_string(), value.to_string());
+        Ok(self)
+    }
+
+    /// Add a context entry; keys containing `=` are rejected
+    pub fn with_context(mut self, key: &str, value: &str) -> Result<Self, Asc100Error> {
+        check_key(key)?;
+        self.context.insert(key.to_string(), value.to_string());
+        Ok(self)
+    }
+
+    pub fn with_content_type(mut self, content_type: &str) -> Self {
+        self.meta.insert(META_CONTENT_TYPE.to_string(), content_type.to_string());
+        self
+    }
+
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.meta.insert(META_TIMESTAMP.to_string(), timestamp.to_string());
+        self
+    }
+
+    pub fn with_origin(mut self, origin: &str) -> Self {
+        self.meta.insert(META_ORIGIN.to_string(), origin.to_string());
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.meta.get(key).map(String::as_str)
+    }
+
+    pub fn get_context(&self, key: &str) -> Option<&str> {
+        self.context.get(key).map(String::as_str)
+    }
+
+    /// Parse a metadata value, `None` when missing or not parseable as `T`
+    pub fn get_as<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.get(key).and_then(|value| value.parse().ok())
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.get(META_CONTENT_TYPE)
+    }
+
+    pub fn timestamp(&self) -> Option<u64> {
+        self.get_as(META_TIMESTAMP)
+    }
+
+    pub fn origin(&self) -> Option<&str> {
+        self.get(META_ORIGIN)
+    }
+
+    /// Parse envelope text
+    pub fn parse(text: &str) -> Result<Self, Asc100Error> {
+        let tokens = tokenize(text);
+        let mut i = 0;
+
+        let meta = parse_section(&tokens, &mut i, MARKER_MEM)?;
+        let context = parse_section(&tokens, &mut i, MARKER_CTX)?;
+
+        let header_len: usize = tokens[..i].iter().map(Token::text_len).sum();
+        Ok(Self { meta, context, body: text[header_len..].to_string() })
+    }
+
+    /// Decode an ASC100 payload holding an envelope
+    pub fn from_encoded(encoded: &str, version: &Asc100Version) -> Result<Self, Asc100Error> {
+        Self::parse(&version.decode_with(encoded, &ExtensionsStrategy::strict())?)
+    }
+
+    /// Encode this envelope as an ASC100 payload
+    ///
+    /// Fails if a key inserted directly into `meta` or `context` contains `=`
+    pub fn encode(&self, version: &Asc100Version) -> Result<String, Asc100Error> {
+        self.meta.keys().chain(self.context.keys()).try_for_each(|key| check_key(key))?;
+        version.encode_with(&self.to_string(), &ExtensionsStrategy::strict())
+    }
+}
+
+impl std::fmt::Display for Envelope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#MEM#{}#MEM#", format_section(&self.meta))?;
+        write!(f, "#CTX#{}#CTX#", format_section(&self.context))?;
+        write!(f, "{}", self.body)
+    }
+}
+
+fn check_key(key: &str) -> Result<(), Asc100Error> {
+    if key.contains('=') {
+        return Err(Asc100Error::MalformedEnvelope(format!("key contains '=': {}", key)));
+    }
+    Ok(())
+}
+
+fn format_section(entries: &BTreeMap<String, String>) -> String {
+    entries.iter()
+        .map(|(key, value)| format!("{}={}", escape_text(key), escape_text(value)))
+        .collect::<Vec<_>>()
+        .join("#BRK#")
+}
+
+/// Parse a `#MEM#...#MEM#` style section starting at `tokens[*i]`
+fn parse_section(tokens: &[Token], i: &mut usize, marker: u8) -> Result<BTreeMap<String, String>, Asc100Error> {
+    let name = crate::char::charset::marker_name(marker).unwrap_or("");
+    if !tokens.get(*i).is_some_and(|token| token.is_marker(marker)) {
+        return Err(Asc100Error::MalformedEnvelope(format!("expected {} section", name)));
+    }
+    *i += 1;
+
+    let mut raw_entries = Vec::new();
+    let mut entry = String::new();
+
+    while let Some(token) = tokens.get(*i) {
+        *i += 1;
+        if token.is_marker(MARKER_BRK) {
+            raw_entries.push(std::mem::take(&mut entry));
+        } else if token.is_marker(marker) {
+            // An empty section has no entries at all
+            if !raw_entries.is_empty() || !entry.is_empty() {
+                raw_entries.push(entry);
+            }
+            return raw_entries.iter()
+                .map(|raw| {
+                    raw.split_once('=')
+                        .map(|(key, value)| (key.to_string(), value.to_string()))
+                        .ok_or_else(|| Asc100Error::MalformedEnvelope(format!("{} entry without '=': {}", name, raw)))
+                })
+                .collect();
+        } else {
+            push_unescaped(&mut entry, token);
+        }
+    }
+
+    Err(Asc100Error::MalformedEnvelope(format!("unterminated {} section", name)))
+}
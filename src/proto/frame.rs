@@ -0,0 +1,222 @@
+//! Multi-message framing with `#SSX#` / `#ESX#`
+//!
+//! Each message is ASC100-encoded and written as `#SSX#[seq=N;][len=N;]payload#ESX#`
+//! followed by a newline. The markers appear as literal text on the wire: the
+//! Base64 payload alphabet never contains `#`, `;` or `=`, so a reader can always
+//! resynchronise on the next `#SSX#` after a corrupted frame.
+
+use std::io::{Read, Write};
+
+use crate::char::extensions::{EncodingStrategy, ExtensionsStrategy, StrictFilter};
+use crate::char::versions::Asc100Version;
+use crate::Asc100Error;
+
+const FRAME_START: &[u8] = b"#SSX#";
+const FRAME_END: &[u8] = b"#ESX#";
+
+/// A decoded frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub sequence: Option<u64>,
+    pub message: String,
+}
+
+/// Writes ASC100-encoded messages as frames
+pub struct FrameWriter<W: Write, S: EncodingStrategy = ExtensionsStrategy<StrictFilter>> {
+    inner: W,
+    version: Asc100Version,
+    strategy: S,
+    next_sequence: Option<u64>,
+    with_length: bool,
+}
+
+impl<W: Write> FrameWriter<W> {
+    /// Frame writer using the extensions strategy
+    pub fn new(inner: W, version: &Asc100Version) -> Self {
+        Self::with_strategy(inner, version, ExtensionsStrategy::strict())
+    }
+}
+
+impl<W: Write, S: EncodingStrategy> FrameWriter<W, S> {
+    pub fn with_strategy(inner: W, version: &Asc100Version, strategy: S) -> Self {
+        Self { inner, version: *version, strategy, next_sequence: None, with_length: false }
+    }
+
+    /// Number frames starting from zero
+    pub fn sequenced(mut self) -> Self {
+        self.next_sequence = Some(0);
+        self
+    }
+
+    /// Record the payload length so truncated frames are detected
+    pub fn with_length(mut self) -> Self {
+        self.with_length = true;
+        self
+    }
+
+    /// Encode and write one message as a frame
+    pub fn write_message(&mut self, message: &str) -> Result<(), Asc100Error> {
+        let payload = self.version.encode_with(message, &self.strategy)?;
+
+        let mut frame = String::from("#SSX#");
+        if let Some(sequence) = self.next_sequence.as_mut() {
+            frame.push_str(&format!("seq={};", sequence));
+            *sequence += 1;
+        }
+        if self.with_length {
+            frame.push_str(&format!("len={};", payload.len()));
+        }
+        frame.push_str(&payload);
+        frame.push_str("#ESX#\n");
+
+        self.inner.write_all(frame.as_bytes()).map_err(io_error)
+    }
+
+    pub fn flush(&mut self) -> Result<(), Asc100Error> {
+        self.inner.flush().map_err(io_error)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Reads frames from a byte stream, skipping past corrupted ones
+///
+/// Iterating yields one item per frame; a corrupted frame yields an error and
+/// reading continues at the next `#SSX#`.
+pub struct FrameReader<R: Read, S: EncodingStrategy = ExtensionsStrategy<StrictFilter>> {
+    inner: R,
+    version: Asc100Version,
+    strategy: S,
+    buffer: Vec<u8>,
+    /// Stream offset of `buffer[0]`
+    offset: usize,
+    eof: bool,
+}
+
+impl<R: Read> FrameReader<R> {
+    /// Frame reader using the extensions strategy
+    pub fn new(inner: R, version: &Asc100Version) -> Self {
+        Self::with_strategy(inner, version, ExtensionsStrategy::strict())
+    }
+}
+
+impl<R: Read, S: EncodingStrategy> FrameReader<R, S> {
+    pub fn with_strategy(inner: R, version: &Asc100Version, strategy: S) -> Self {
+        Self { inner, version: *version, strategy, buffer: Vec::new(), offset: 0, eof: false }
+    }
+
+    /// Read the next frame, `None` once the stream is exhausted
+    pub fn next_frame(&mut self) -> Option<Result<Frame, Asc100Error>> {
+        loop {
+            let Some(start) = find(&self.buffer, FRAME_START, 0) else {
+                if self.eof {
+                    return None;
+                }
+                // Keep a possible partial marker at the end of the buffer
+                let keep = self.buffer.len().min(FRAME_START.len() - 1);
+                self.consume(self.buffer.len() - keep);
+                if let Err(e) = self.fill() {
+                    return Some(Err(e));
+                }
+                continue;
+            };
+
+            let body_start = start + FRAME_START.len();
+            let end = find(&self.buffer, FRAME_END, body_start);
+            let next_start = find(&self.buffer, FRAME_START, body_start);
+            let frame_offset = self.offset + start;
+
+            match (end, next_start) {
+                (Some(end), next) if !matches!(next, Some(next) if next < end) => {
+                    let body = String::from_utf8_lossy(&self.buffer[body_start..end]).into_owned();
+                    self.consume(end + FRAME_END.len());
+                    return Some(self.parse_frame(&body, frame_offset));
+                }
+                (_, Some(next)) => {
+                    self.consume(next);
+                    return Some(Err(corrupt(frame_offset, "frame not terminated before next #SSX#")));
+                }
+                _ if self.eof => {
+                    self.consume(self.buffer.len());
+                    return Some(Err(corrupt(frame_offset, "stream ended inside frame")));
+                }
+                _ => {
+                    if let Err(e) = self.fill() {
+                        return Some(Err(e));
+                    }
+                }
+            }
+        }
+    }
+
+    fn parse_frame(&self, body: &str, frame_offset: usize) -> Result<Frame, Asc100Error> {
+        let mut parts: Vec<&str> = body.split(';').collect();
+        let payload = parts.pop().unwrap_or("");
+
+        let mut sequence = None;
+        for field in parts {
+            match field.split_once('=') {
+                Some(("seq", value)) => {
+                    sequence = Some(value.parse().map_err(|_| corrupt(frame_offset, "invalid sequence number"))?);
+                }
+                Some(("len", value)) => {
+                    let expected: usize = value.parse().map_err(|_| corrupt(frame_offset, "invalid length"))?;
+                    if expected != payload.len() {
+                        return Err(corrupt(frame_offset, &format!(
+                            "length mismatch: header says {}, payload has {}", expected, payload.len()
+                        )));
+                    }
+                }
+                _ => return Err(corrupt(frame_offset, &format!("unknown header field: {}", field))),
+            }
+        }
+
+        let message = self.version.decode_with(payload, &self.strategy)
+            .map_err(|e| corrupt(frame_offset, &e.to_string()))?;
+        Ok(Frame { sequence, message })
+    }
+
+    fn fill(&mut self) -> Result<(), Asc100Error> {
+        let mut chunk = [0u8; 4096];
+        let read = self.inner.read(&mut chunk).map_err(|e| {
+            // A failed stream cannot be resumed
+            self.eof = true;
+            io_error(e)
+        })?;
+        if read == 0 {
+            self.eof = true;
+        }
+        self.buffer.extend_from_slice(&chunk[..read]);
+        Ok(())
+    }
+
+    fn consume(&mut self, count: usize) {
+        self.buffer.drain(..count);
+        self.offset += count;
+    }
+}
+
+impl<R: Read, S: EncodingStrategy> Iterator for FrameReader<R, S> {
+    type Item = Result<Frame, Asc100Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_frame()
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack.get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|pos| pos + from)
+}
+
+fn corrupt(offset: usize, reason: &str) -> Asc100Error {
+    Asc100Error::CorruptFrame { offset, reason: reason.to_string() }
+}
+
+fn io_error(error: std::io::Error) -> Asc100Error {
+    Asc100Error::Io(error.to_string())
+}
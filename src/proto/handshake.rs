@@ -0,0 +1,269 @@
+//! Handshake protocol over `#HSO#` / `#HSI#` / `#ACK#`
+//!
+//! A transport-agnostic state machine. The initiator sends its capabilities in
+//! an `#HSO#` message, the responder answers with the agreed setup in `#HSI#`,
+//! and the initiator confirms it with `#ACK#`. Handshake messages are always
+//! encoded with `V1_STANDARD` and the extensions strategy, since no version has
+//! been agreed yet when they are exchanged.
+
+use super::tokens::{Token, tokenize, escape_text, push_unescaped};
+use crate::char::charset::{marker_name, MARKER_ACK, MARKER_BRK, MARKER_HSI, MARKER_HSO};
+use crate::char::extensions::ExtensionsStrategy;
+use crate::char::versions::{V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL};
+use crate::Asc100Error;
+
+/// What one side supports, in order of preference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    pub versions: Vec<String>,
+    pub strategies: Vec<String>,
+    pub markers: Vec<u8>,
+}
+
+impl Default for Capabilities {
+    /// All built-in versions, both strategies and every V1 marker
+    fn default() -> Self {
+        Self {
+            versions: [V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL].iter().map(|v| v.name.to_string()).collect(),
+            strategies: vec!["extensions".to_string(), "core".to_string()],
+            markers: crate::char::charset::MARKERS.iter().map(|(_, index)| *index).collect(),
+        }
+    }
+}
+
+/// The setup both sides agreed on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Agreement {
+    pub version: String,
+    pub strategy: String,
+    pub markers: Vec<u8>,
+}
+
+impl Capabilities {
+    /// Pick the best common setup, preferring the order of `self`
+    pub fn negotiate(&self, peer: &Capabilities) -> Result<Agreement, Asc100Error> {
+        let version = self.versions.iter().find(|v| peer.versions.contains(v))
+            .ok_or_else(|| Asc100Error::HandshakeFailed("no common version".to_string()))?;
+        let strategy = self.strategies.iter().find(|s| peer.strategies.contains(s))
+            .ok_or_else(|| Asc100Error::HandshakeFailed("no common strategy".to_string()))?;
+
+        let mut markers: Vec<u8> = self.markers.iter().copied().filter(|m| peer.markers.contains(m)).collect();
+        markers.sort_unstable();
+        markers.dedup();
+
+        Ok(Agreement { version: version.clone(), strategy: strategy.clone(), markers })
+    }
+
+    /// Check that every version and strategy name survives the message format:
+    /// non-empty, without the `,` list separator and without `#` marker text
+    pub fn validate(&self) -> Result<(), Asc100Error> {
+        let names = self.versions.iter().map(|name| ("version", name))
+            .chain(self.strategies.iter().map(|name| ("strategy", name)));
+        for (kind, name) in names {
+            if name.is_empty() || name.contains([',', '#']) {
+                return Err(Asc100Error::HandshakeFailed(format!("invalid {} name: {:?}", kind, name)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether an agreement only uses what these capabilities allow
+    pub fn permits(&self, agreement: &Agreement) -> bool {
+        self.versions.contains(&agreement.version)
+            && self.strategies.contains(&agreement.strategy)
+            && agreement.markers.iter().all(|m| self.markers.contains(m))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Initiator,
+    Responder,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandshakeState {
+    /// Nothing exchanged yet
+    Idle,
+    /// Initiator sent `#HSO#` and waits for `#HSI#`
+    AwaitingResponse,
+    /// Responder sent `#HSI#` and waits for `#ACK#`
+    AwaitingAck(Agreement),
+    Established(Agreement),
+}
+
+/// Handshake state machine for one side of a connection
+#[derive(Debug, Clone)]
+pub struct Handshake {
+    role: Role,
+    local: Capabilities,
+    state: HandshakeState,
+}
+
+impl Handshake {
+    pub fn initiator(local: Capabilities) -> Self {
+        Self { role: Role::Initiator, local, state: HandshakeState::Idle }
+    }
+
+    pub fn responder(local: Capabilities) -> Self {
+        Self { role: Role::Responder, local, state: HandshakeState::Idle }
+    }
+
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    pub fn state(&self) -> &HandshakeState {
+        &self.state
+    }
+
+    pub fn agreement(&self) -> Option<&Agreement> {
+        match &self.state {
+            HandshakeState::Established(agreement) => Some(agreement),
+            _ => None,
+        }
+    }
+
+    pub fn is_established(&self) -> bool {
+        self.agreement().is_some()
+    }
+
+    /// Produce the opening `#HSO#` message (initiator only)
+    pub fn start(&mut self) -> Result<String, Asc100Error> {
+        if self.role != Role::Initiator || self.state != HandshakeState::Idle {
+            return Err(unexpected("start"));
+        }
+        self.local.validate()?;
+        let message = encode_message(MARKER_HSO, &format_capabilities(&self.local))?;
+        self.state = HandshakeState::AwaitingResponse;
+        Ok(message)
+    }
+
+    /// Consume an encoded handshake message, returning the reply to send, if any
+    pub fn receive(&mut self, encoded: &str) -> Result<Option<String>, Asc100Error> {
+        let (marker, fields) = decode_message(encoded)?;
+
+        match (self.role, &self.state, marker) {
+            (Role::Responder, HandshakeState::Idle, MARKER_HSO) => {
+                self.local.validate()?;
+                let peer = parse_capabilities(&fields)?;
+                // The initiator's preferences decide between common options
+                let agreement = peer.negotiate(&self.local)?;
+                let reply = encode_message(MARKER_HSI, &format_agreement(&agreement))?;
+                self.state = HandshakeState::AwaitingAck(agreement);
+                Ok(Some(reply))
+            }
+            (Role::Initiator, HandshakeState::AwaitingResponse, MARKER_HSI) => {
+                let agreement = parse_agreement(&fields)?;
+                if !self.local.permits(&agreement) {
+                    return Err(Asc100Error::HandshakeFailed("responder chose an unsupported setup".to_string()));
+                }
+                let reply = encode_message(MARKER_ACK, &format_agreement(&agreement))?;
+                self.state = HandshakeState::Established(agreement);
+                Ok(Some(reply))
+            }
+            (Role::Responder, HandshakeState::AwaitingAck(expected), MARKER_ACK) => {
+                let agreement = parse_agreement(&fields)?;
+                if &agreement != expected {
+                    return Err(Asc100Error::HandshakeFailed("acknowledged setup differs from offer".to_string()));
+                }
+                self.state = HandshakeState::Established(agreement);
+                Ok(None)
+            }
+            _ => Err(unexpected(marker_name(marker).unwrap_or("message"))),
+        }
+    }
+}
+
+fn unexpected(what: &str) -> Asc100Error {
+    Asc100Error::HandshakeFailed(format!("unexpected {} for current state", what))
+}
+
+fn format_capabilities(caps: &Capabilities) -> Vec<(&'static str, String)> {
+    vec![
+        ("versions", caps.versions.join(",")),
+        ("strategies", caps.strategies.join(",")),
+        ("markers", join_markers(&caps.markers)),
+    ]
+}
+
+fn format_agreement(agreement: &Agreement) -> Vec<(&'static str, String)> {
+    vec![
+        ("version", agreement.version.clone()),
+        ("strategy", agreement.strategy.clone()),
+        ("markers", join_markers(&agreement.markers)),
+    ]
+}
+
+fn join_markers(markers: &[u8]) -> String {
+    markers.iter().map(u8::to_string).collect::<Vec<_>>().join(",")
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value.split(',').filter(|item| !item.is_empty()).map(str::to_string).collect()
+}
+
+fn parse_markers(value: &str) -> Result<Vec<u8>, Asc100Error> {
+    split_list(value).iter()
+        .map(|item| item.parse().map_err(|_| Asc100Error::HandshakeFailed(format!("invalid marker index: {}", item))))
+        .collect()
+}
+
+fn field<'a>(fields: &'a [(String, String)], key: &str) -> Result<&'a str, Asc100Error> {
+    fields.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+        .ok_or_else(|| Asc100Error::HandshakeFailed(format!("missing field: {}", key)))
+}
+
+fn parse_capabilities(fields: &[(String, String)]) -> Result<Capabilities, Asc100Error> {
+    Ok(Capabilities {
+        versions: split_list(field(fields, "versions")?),
+        strategies: split_list(field(fields, "strategies")?),
+        markers: parse_markers(field(fields, "markers")?)?,
+    })
+}
+
+fn parse_agreement(fields: &[(String, String)]) -> Result<Agreement, Asc100Error> {
+    Ok(Agreement {
+        version: field(fields, "version")?.to_string(),
+        strategy: field(fields, "strategy")?.to_string(),
+        markers: parse_markers(field(fields, "markers")?)?,
+    })
+}
+
+/// Encode `#XXX#k=v#BRK#k=v` with the bootstrap version and strategy
+fn encode_message(marker: u8, fields: &[(&str, String)]) -> Result<String, Asc100Error> {
+    let body = fields.iter()
+        .map(|(key, value)| format!("{}={}", key, escape_text(value)))
+        .collect::<Vec<_>>()
+        .join("#BRK#");
+    let text = format!("{}{}", marker_name(marker).unwrap_or(""), body);
+    V1_STANDARD.encode_with(&text, &ExtensionsStrategy::strict())
+}
+
+fn decode_message(encoded: &str) -> Result<(u8, Vec<(String, String)>), Asc100Error> {
+    let text = V1_STANDARD.decode_with(encoded, &ExtensionsStrategy::strict())?;
+    let tokens = tokenize(&text);
+
+    let marker = match tokens.first() {
+        Some(Token::Marker(marker @ (MARKER_HSO | MARKER_HSI | MARKER_ACK))) => *marker,
+        _ => return Err(Asc100Error::HandshakeFailed("not a handshake message".to_string())),
+    };
+
+    let mut raw_fields = vec![String::new()];
+    for token in &tokens[1..] {
+        if token.is_marker(MARKER_BRK) {
+            raw_fields.push(String::new());
+        } else if let Some(current) = raw_fields.last_mut() {
+            push_unescaped(current, token);
+        }
+    }
+
+    let fields = raw_fields.iter()
+        .filter(|raw| !raw.is_empty())
+        .map(|raw| raw.split_once('=')
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .ok_or_else(|| Asc100Error::HandshakeFailed(format!("invalid field: {}", raw))))
+        .collect::<Result<_, _>>()?;
+
+    Ok((marker, fields))
+}
@@ -0,0 +1,214 @@
+//! Marker structure validation
+//!
+//! Checks that marker pairs and sequences in a payload are well formed:
+//! stream blocks, variables, function arguments and end-of-file handling.
+
+use super::tokens::{Token, tokenize};
+use crate::char::charset::{MARKERS, MARKER_ARG, MARKER_EOF, MARKER_ESX, MARKER_FX, MARKER_SSX, MARKER_V};
+use crate::char::extensions::EncodingStrategy;
+
+/// How serious a marker issue is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Kind of marker structure problem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IssueKind {
+    /// #SSX# without a matching #ESX#
+    UnclosedStream,
+    /// #ESX# without an open #SSX#
+    UnopenedStream,
+    /// #V# without a closing #V# around a plain name
+    UnterminatedVariable,
+    /// #ARG# with no preceding #FX#
+    OrphanArgument,
+    /// Anything following #EOF#
+    ContentAfterEof,
+    /// Text that looks like a marker but is not one (e.g. `#VAR#`)
+    UnknownMarker(String),
+}
+
+/// A single finding from `validate_markers`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkerIssue {
+    pub severity: Severity,
+    pub position: usize,
+    pub kind: IssueKind,
+}
+
+impl MarkerIssue {
+    fn new(severity: Severity, position: usize, kind: IssueKind) -> Self {
+        Self { severity, position, kind }
+    }
+}
+
+impl std::fmt::Display for MarkerIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match &self.kind {
+            IssueKind::UnclosedStream => "#SSX# is never closed by #ESX#".to_string(),
+            IssueKind::UnopenedStream => "#ESX# has no matching #SSX#".to_string(),
+            IssueKind::UnterminatedVariable => "#V# variable is not terminated".to_string(),
+            IssueKind::OrphanArgument => "#ARG# appears without #FX#".to_string(),
+            IssueKind::ContentAfterEof => "Content after #EOF#".to_string(),
+            IssueKind::UnknownMarker(candidate) => format!("Unknown marker {}", candidate),
+        };
+        write!(f, "{:?} at position {}: {}", self.severity, self.position, message)
+    }
+}
+
+/// Check marker structure, returning issues ordered by position
+pub fn validate_markers(tokens: &[Token]) -> Vec<MarkerIssue> {
+    let mut issues = Vec::new();
+    let mut open_streams = Vec::new();
+    let mut seen_function = false;
+    let mut eof_reported = false;
+    let mut eof_seen = false;
+    let mut position = 0;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = &tokens[i];
+
+        if eof_seen && !eof_reported {
+            issues.push(MarkerIssue::new(Severity::Warning, position, IssueKind::ContentAfterEof));
+            eof_reported = true;
+        }
+
+        match token {
+            Token::Text(text) => check_lookalikes(text, position, &mut issues),
+            Token::Marker(MARKER_SSX) => open_streams.push(position),
+            Token::Marker(MARKER_ESX) => {
+                if open_streams.pop().is_none() {
+                    issues.push(MarkerIssue::new(Severity::Error, position, IssueKind::UnopenedStream));
+                }
+            }
+            Token::Marker(MARKER_V) => {
+                // Expect #V#name#V# - a plain text name followed by the closing marker
+                let closed = matches!(tokens.get(i + 1), Some(Token::Text(_)))
+                    && tokens.get(i + 2).is_some_and(|t| t.is_marker(MARKER_V));
+                if closed {
+                    // Consume the name and closer so the closer is not read as an opener
+                    position += tokens[i..i + 3].iter().map(Token::text_len).sum::<usize>();
+                    i += 3;
+                    continue;
+                }
+                issues.push(MarkerIssue::new(Severity::Error, position, IssueKind::UnterminatedVariable));
+            }
+            Token::Marker(MARKER_FX) => seen_function = true,
+            Token::Marker(MARKER_ARG) => {
+                if !seen_function {
+                    issues.push(MarkerIssue::new(Severity::Error, position, IssueKind::OrphanArgument));
+                }
+            }
+            Token::Marker(MARKER_EOF) => eof_seen = true,
+            Token::Marker(_) => {}
+        }
+
+        position += token.text_len();
+        i += 1;
+    }
+
+    for start in open_streams {
+        issues.push(MarkerIssue::new(Severity::Error, start, IssueKind::UnclosedStream));
+    }
+
+    issues.sort_by_key(|issue| issue.position);
+    issues
+}
+
+/// Tokenize text and validate its markers in one step
+pub fn validate_text(text: &str) -> Vec<MarkerIssue> {
+    validate_markers(&tokenize(text))
+}
+
+/// Flag `#WORD#` candidates in literal text that are not known markers
+fn check_lookalikes(text: &str, offset: usize, issues: &mut Vec<MarkerIssue>) {
+    let mut chars = text.char_indices();
+
+    while let Some((start, ch)) = chars.next() {
+        if ch != '#' {
+            continue;
+        }
+
+        let mut end = None;
+        for (pos, next_ch) in chars.by_ref() {
+            if next_ch == '#' {
+                end = Some(pos);
+                break;
+            }
+        }
+
+        let Some(end) = end else { break };
+        let word = &text[start + 1..end];
+        let looks_like_marker = !word.is_empty()
+            && word.len() <= 8
+            && word.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+            && word.chars().next().is_some_and(|c| c.is_ascii_uppercase());
+
+        let candidate = &text[start..=end];
+        if looks_like_marker && !MARKERS.iter().any(|(marker, _)| *marker == candidate) {
+            issues.push(MarkerIssue::new(
+                Severity::Warning,
+                offset + start,
+                IssueKind::UnknownMarker(candidate.to_string()),
+            ));
+        }
+    }
+}
+
+/// Linting strategy - runs `validate_markers` while encoding and rejects
+/// payloads with error-level issues
+pub struct LintedStrategy<S: EncodingStrategy> {
+    pub inner: S,
+}
+
+impl<S: EncodingStrategy> LintedStrategy<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S: EncodingStrategy> EncodingStrategy for LintedStrategy<S> {
+    fn preprocess(&self, input: &str) -> Result<String, crate::Asc100Error> {
+        let filtered = self.inner.preprocess(input)?;
+
+        // Markers the inner strategy does not support are plain text
+        let tokens: Vec<Token> = tokenize(&filtered)
+            .into_iter()
+            .map(|token| match token {
+                Token::Marker(index) if !self.inner.supports_index(index) => {
+                    Token::Text(crate::char::charset::marker_name(index).unwrap_or("").to_string())
+                }
+                other => other,
+            })
+            .collect();
+
+        if let Some(issue) = validate_markers(&tokens)
+            .into_iter()
+            .find(|issue| issue.severity == Severity::Error)
+        {
+            return Err(crate::Asc100Error::MalformedMarkers(issue));
+        }
+
+        Ok(filtered)
+    }
+
+    fn postprocess(&self, output: &str) -> String {
+        self.inner.postprocess(output)
+    }
+
+    fn supports_index(&self, index: u8) -> bool {
+        self.inner.supports_index(index)
+    }
+
+    fn encode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        self.inner.encode_indices(indices)
+    }
+
+    fn decode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, crate::Asc100Error> {
+        self.inner.decode_indices(indices)
+    }
+}
@@ -0,0 +1,27 @@
+//! Marker protocol helpers
+//!
+//! Structured views over the V1 extension markers: linting, and the higher level
+//! protocols (templates, function calls, trust zones, ...) built on top of them.
+
+pub mod tokens;
+pub mod lint;
+pub mod template;
+pub mod call;
+pub mod trust;
+pub mod envelope;
+pub mod handshake;
+pub mod frame;
+pub mod chunk;
+pub mod records;
+
+// Re-export commonly used items
+pub use tokens::{Token, tokenize, render_tokens, escape_text};
+pub use lint::{validate_markers, validate_text, MarkerIssue, IssueKind, Severity, LintedStrategy};
+pub use template::{Template, TemplatePart, render_encoded};
+pub use call::{FunctionCall, Dispatcher, parse_calls};
+pub use trust::{Trust, TrustSegment, TrustAction, TrustPolicy, trust_segments, decode_trust_segments, decode_trusted};
+pub use envelope::{Envelope, META_CONTENT_TYPE, META_TIMESTAMP, META_ORIGIN};
+pub use handshake::{Handshake, HandshakeState, Capabilities, Agreement, Role};
+pub use frame::{Frame, FrameWriter, FrameReader};
+pub use chunk::{Part, Splitter, Reassembler, MAX_PARTS, DEFAULT_MAX_PENDING};
+pub use records::{Record, encode_records, decode_records, encode_nested, decode_nested, encode_records_with, decode_records_with};
@@ -0,0 +1,165 @@
+//! Record lists separated by `#BRK#`
+//!
+//! Records are joined with `#BRK#` and their content escaped (`#` and `"` as
+//! `#E#` and `#Q#`), so any text can appear inside a record without colliding
+//! with the separator. A nested list is wrapped in `#SSX#...#ESX#` and takes the
+//! place of one record.
+//!
+//! At the top level an empty list and a list holding one empty record both
+//! render as empty text; decoding empty text yields one empty record.
+
+use super::tokens::{Token, tokenize, escape_text, push_unescaped};
+use crate::char::charset::{marker_name, MARKER_BRK, MARKER_ESX, MARKER_SSX};
+use crate::char::extensions::ExtensionsStrategy;
+use crate::char::versions::Asc100Version;
+use crate::Asc100Error;
+
+/// A record or a nested list of records
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Record {
+    Item(String),
+    List(Vec<Record>),
+}
+
+impl Record {
+    pub fn item(text: &str) -> Self {
+        Record::Item(text.to_string())
+    }
+
+    pub fn list(items: &[&str]) -> Self {
+        Record::List(items.iter().map(|item| Record::item(item)).collect())
+    }
+}
+
+/// Join records into marker text
+pub fn encode_records(records: &[&str]) -> String {
+    records.iter().map(|record| escape_text(record)).collect::<Vec<_>>().join("#BRK#")
+}
+
+/// Split marker text back into records
+///
+/// Only the top level is split: a nested list comes back as its inner record
+/// text, which can be passed to `decode_records` again.
+pub fn decode_records(text: &str) -> Vec<String> {
+    let mut records = vec![String::new()];
+    let mut depth = 0usize;
+
+    for token in tokenize(text) {
+        let Some(current) = records.last_mut() else { break };
+        match token {
+            Token::Marker(MARKER_BRK) if depth == 0 => records.push(String::new()),
+            Token::Marker(MARKER_SSX) => {
+                if depth > 0 {
+                    current.push_str("#SSX#");
+                }
+                depth += 1;
+            }
+            Token::Marker(MARKER_ESX) if depth > 0 => {
+                depth -= 1;
+                if depth > 0 {
+                    current.push_str("#ESX#");
+                }
+            }
+            // Nested content stays escaped for the next level
+            Token::Marker(index) if depth > 0 => {
+                current.push_str(marker_name(index).unwrap_or(""));
+            }
+            Token::Text(text) if depth > 0 => current.push_str(&text),
+            token => push_unescaped(current, &token),
+        }
+    }
+
+    records
+}
+
+/// Render nested records into marker text
+pub fn encode_nested(records: &[Record]) -> String {
+    records.iter()
+        .map(|record| match record {
+            Record::Item(text) => escape_text(text),
+            Record::List(inner) => format!("#SSX#{}#ESX#", encode_nested(inner)),
+        })
+        .collect::<Vec<_>>()
+        .join("#BRK#")
+}
+
+/// Parse marker text into nested records
+pub fn decode_nested(text: &str) -> Result<Vec<Record>, Asc100Error> {
+    let tokens = tokenize(text);
+    let mut i = 0;
+    let records = parse_level(&tokens, &mut i, 0)?;
+    if i < tokens.len() {
+        return Err(malformed(&tokens, i, "#ESX# without matching #SSX#"));
+    }
+    Ok(records)
+}
+
+/// Encode records as an ASC100 payload
+pub fn encode_records_with(records: &[&str], version: &Asc100Version) -> Result<String, Asc100Error> {
+    version.encode_with(&encode_records(records), &ExtensionsStrategy::strict())
+}
+
+/// Decode an ASC100 payload holding records
+pub fn decode_records_with(encoded: &str, version: &Asc100Version) -> Result<Vec<String>, Asc100Error> {
+    Ok(decode_records(&version.decode_with(encoded, &ExtensionsStrategy::strict())?))
+}
+
+/// Parse records until the end of input or the `#ESX#` closing this level
+fn parse_level(tokens: &[Token], i: &mut usize, depth: usize) -> Result<Vec<Record>, Asc100Error> {
+    let mut records = Vec::new();
+    let mut text = String::new();
+    let mut list: Option<Vec<Record>> = None;
+
+    // `#SSX##ESX#` is an empty nested list rather than one empty record
+    if depth > 0 && tokens.get(*i).is_some_and(|token| token.is_marker(MARKER_ESX)) {
+        return Ok(records);
+    }
+
+    while let Some(token) = tokens.get(*i) {
+        match token {
+            Token::Marker(MARKER_BRK) => {
+                records.push(finish(&mut text, list.take()));
+            }
+            Token::Marker(MARKER_ESX) if depth > 0 => break,
+            Token::Marker(MARKER_ESX) => return Ok(finish_level(records, text, list)),
+            Token::Marker(MARKER_SSX) => {
+                if list.is_some() || !text.is_empty() {
+                    return Err(malformed(tokens, *i, "nested list must be a whole record"));
+                }
+                let start = *i;
+                *i += 1;
+                let inner = parse_level(tokens, i, depth + 1)?;
+                if !tokens.get(*i).is_some_and(|token| token.is_marker(MARKER_ESX)) {
+                    return Err(malformed(tokens, start, "#SSX# is never closed"));
+                }
+                list = Some(inner);
+            }
+            token => {
+                if list.is_some() {
+                    return Err(malformed(tokens, *i, "text after nested list"));
+                }
+                push_unescaped(&mut text, token);
+            }
+        }
+        *i += 1;
+    }
+
+    Ok(finish_level(records, text, list))
+}
+
+fn finish(text: &mut String, list: Option<Vec<Record>>) -> Record {
+    match list {
+        Some(inner) => Record::List(inner),
+        None => Record::Item(std::mem::take(text)),
+    }
+}
+
+fn finish_level(mut records: Vec<Record>, mut text: String, list: Option<Vec<Record>>) -> Vec<Record> {
+    records.push(finish(&mut text, list));
+    records
+}
+
+fn malformed(tokens: &[Token], index: usize, reason: &str) -> Asc100Error {
+    let position: usize = tokens[..index].iter().map(Token::text_len).sum();
+    Asc100Error::MalformedRecords(format!("{} at position {}", reason, position))
+}
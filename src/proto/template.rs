@@ -0,0 +1,155 @@
+//! Template engine for `#V#name#V#` placeholders
+//!
+//! Templates are parsed from decoded (or pre-encoding) text. A placeholder may
+//! carry an inline default with `#V#name=default#V#`; placeholders without a
+//! default are required when rendering.
+
+use std::collections::HashMap;
+
+use super::lint::{IssueKind, MarkerIssue, Severity};
+use super::tokens::{Token, tokenize};
+use crate::char::charset::{marker_name, MARKER_V};
+use crate::char::extensions::ExtensionsStrategy;
+use crate::char::versions::Asc100Version;
+use crate::Asc100Error;
+
+/// A literal run or a variable placeholder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplatePart {
+    Literal(String),
+    Variable { name: String, default: Option<String> },
+}
+
+/// Parsed `#V#` template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<TemplatePart>,
+}
+
+impl Template {
+    /// Parse template text containing `#V#name#V#` placeholders
+    pub fn parse(text: &str) -> Result<Self, Asc100Error> {
+        let tokens = tokenize(text);
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut position = 0;
+        let mut i = 0;
+
+        while i < tokens.len() {
+            match &tokens[i] {
+                Token::Marker(MARKER_V) => {
+                    let name = match (tokens.get(i + 1), tokens.get(i + 2)) {
+                        (Some(Token::Text(name)), Some(closer)) if closer.is_marker(MARKER_V) => name,
+                        _ => {
+                            return Err(Asc100Error::MalformedMarkers(MarkerIssue {
+                                severity: Severity::Error,
+                                position,
+                                kind: IssueKind::UnterminatedVariable,
+                            }));
+                        }
+                    };
+
+                    if !literal.is_empty() {
+                        parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+                    }
+                    let (name, default) = match name.split_once('=') {
+                        Some((name, default)) => (name, Some(default.to_string())),
+                        None => (name.as_str(), None),
+                    };
+                    parts.push(TemplatePart::Variable { name: name.trim().to_string(), default });
+
+                    position += tokens[i..i + 3].iter().map(Token::text_len).sum::<usize>();
+                    i += 3;
+                    continue;
+                }
+                Token::Marker(index) => literal.push_str(marker_name(*index).unwrap_or("")),
+                Token::Text(text) => literal.push_str(text),
+            }
+            position += tokens[i].text_len();
+            i += 1;
+        }
+
+        if !literal.is_empty() {
+            parts.push(TemplatePart::Literal(literal));
+        }
+
+        Ok(Self { parts })
+    }
+
+    /// Decode an ASC100 payload and parse it as a template
+    pub fn from_encoded(encoded: &str, version: &Asc100Version) -> Result<Self, Asc100Error> {
+        let text = version.decode_with(encoded, &ExtensionsStrategy::strict())?;
+        Self::parse(&text)
+    }
+
+    pub fn parts(&self) -> &[TemplatePart] {
+        &self.parts
+    }
+
+    /// Variable names in order of first use
+    pub fn variables(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        for part in &self.parts {
+            if let TemplatePart::Variable { name, .. } = part {
+                if !names.contains(&name.as_str()) {
+                    names.push(name.as_str());
+                }
+            }
+        }
+        names
+    }
+
+    /// Variables that have no inline default
+    pub fn required(&self) -> Vec<&str> {
+        self.variables()
+            .into_iter()
+            .filter(|name| self.default_for(name).is_none())
+            .collect()
+    }
+
+    /// Default value for a variable, if any placeholder declares one
+    pub fn default_for(&self, variable: &str) -> Option<&str> {
+        self.parts.iter().find_map(|part| match part {
+            TemplatePart::Variable { name, default: Some(default) } if name == variable => Some(default.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Required variables missing from `values`
+    pub fn missing(&self, values: &HashMap<&str, &str>) -> Vec<&str> {
+        self.required()
+            .into_iter()
+            .filter(|name| !values.contains_key(name))
+            .collect()
+    }
+
+    /// Render the template, failing on the first missing required variable
+    pub fn render(&self, values: &HashMap<&str, &str>) -> Result<String, Asc100Error> {
+        if let Some(name) = self.missing(values).first() {
+            return Err(Asc100Error::MissingVariable(name.to_string()));
+        }
+
+        let mut result = String::new();
+        for part in &self.parts {
+            match part {
+                TemplatePart::Literal(text) => result.push_str(text),
+                TemplatePart::Variable { name, .. } => {
+                    let value = values.get(name.as_str()).copied()
+                        .or_else(|| self.default_for(name))
+                        .unwrap_or("");
+                    result.push_str(value);
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Decode an ASC100-encoded template and render it in one step
+pub fn render_encoded(
+    encoded: &str,
+    version: &Asc100Version,
+    values: &HashMap<&str, &str>
+) -> Result<String, Asc100Error> {
+    Template::from_encoded(encoded, version)?.render(values)
+}
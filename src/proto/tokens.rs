@@ -0,0 +1,82 @@
+//! Token view of ASC100 text with markers
+//!
+//! Protocol helpers work on decoded (or pre-encoding) text as a sequence of
+//! literal text runs and marker indices, using the same marker scan as encoding.
+
+use crate::char::charset::{marker_name, scan_markers, MARKER_E, MARKER_Q};
+
+/// A literal text run or an extension marker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Text(String),
+    Marker(u8),
+}
+
+impl Token {
+    /// Length of this token in rendered text
+    pub fn text_len(&self) -> usize {
+        match self {
+            Token::Text(text) => text.len(),
+            Token::Marker(index) => marker_name(*index).map_or(0, str::len),
+        }
+    }
+    
+    pub fn is_marker(&self, index: u8) -> bool {
+        matches!(self, Token::Marker(m) if *m == index)
+    }
+}
+
+/// Split text into text runs and markers
+pub fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    
+    for (pos, marker_index) in scan_markers(text) {
+        if pos > text_start {
+            tokens.push(Token::Text(text[text_start..pos].to_string()));
+        }
+        tokens.push(Token::Marker(marker_index));
+        text_start = pos + marker_name(marker_index).map_or(0, str::len);
+    }
+    
+    if text_start < text.len() {
+        tokens.push(Token::Text(text[text_start..].to_string()));
+    }
+    
+    tokens
+}
+
+/// Render tokens back into marker text
+pub fn render_tokens(tokens: &[Token]) -> String {
+    let mut result = String::new();
+    for token in tokens {
+        match token {
+            Token::Text(text) => result.push_str(text),
+            Token::Marker(index) => result.push_str(marker_name(*index).unwrap_or("")),
+        }
+    }
+    result
+}
+
+/// Escape `#` and `"` as `#E#` and `#Q#` so text carries no raw marker characters
+pub fn escape_text(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '#' => result.push_str("#E#"),
+            '"' => result.push_str("#Q#"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// Append a token of escaped text, restoring `#E#` and `#Q#`
+pub(crate) fn push_unescaped(target: &mut String, token: &Token) {
+    match token {
+        Token::Text(text) => target.push_str(text),
+        Token::Marker(MARKER_E) => target.push('#'),
+        Token::Marker(MARKER_Q) => target.push('"'),
+        Token::Marker(index) => target.push_str(marker_name(*index).unwrap_or("")),
+    }
+}
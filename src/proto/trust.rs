@@ -0,0 +1,180 @@
+//! Trust zones using `#TR#` / `#DNT#`
+//!
+//! `#TR#...#TR#` marks trusted content and `#DNT#...#DNT#` marks content that
+//! must not be trusted. Untrusted zones may sit inside trusted ones (a downgrade),
+//! but a trusted zone inside an untrusted one, crossing zones or an unclosed zone
+//! are rejected as mislabeled.
+
+use super::call::{parse_calls, Dispatcher};
+use super::template::Template;
+use super::tokens::{Token, tokenize};
+use crate::char::charset::{marker_name, MARKER_DNT, MARKER_TR};
+use crate::char::extensions::ExtensionsStrategy;
+use crate::char::versions::Asc100Version;
+use crate::Asc100Error;
+
+/// Trust label of a span
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trust {
+    Trusted,
+    Untrusted,
+    Unlabeled,
+}
+
+/// A run of text with a single trust label, without the zone markers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustSegment {
+    pub trust: Trust,
+    pub text: String,
+    /// Byte position of the segment start in the labeled text
+    pub position: usize,
+}
+
+/// Split labeled text into trust segments
+pub fn trust_segments(text: &str) -> Result<Vec<TrustSegment>, Asc100Error> {
+    let mut segments: Vec<TrustSegment> = Vec::new();
+    // Open zones as (marker, position), innermost last
+    let mut zones: Vec<(u8, usize)> = Vec::new();
+    let mut position = 0;
+
+    for token in tokenize(text) {
+        let len = token.text_len();
+        match token {
+            Token::Marker(marker @ (MARKER_TR | MARKER_DNT)) => {
+                match zones.last() {
+                    Some((open, _)) if *open == marker => { zones.pop(); }
+                    Some((open, _)) if zones.iter().any(|(m, _)| *m == marker) => {
+                        return Err(mislabeled(position, &format!(
+                            "{} closed while {} is open",
+                            marker_name(marker).unwrap_or(""),
+                            marker_name(*open).unwrap_or("")
+                        )));
+                    }
+                    Some((MARKER_DNT, _)) => {
+                        return Err(mislabeled(position, "#TR# zone inside #DNT# zone"));
+                    }
+                    _ => zones.push((marker, position)),
+                }
+            }
+            other => {
+                let trust = current_trust(&zones);
+                let content = match &other {
+                    Token::Text(text) => text.as_str(),
+                    Token::Marker(index) => marker_name(*index).unwrap_or(""),
+                };
+                match segments.last_mut() {
+                    Some(last) if last.trust == trust && last.position + last.text.len() == position => {
+                        last.text.push_str(content);
+                    }
+                    _ => segments.push(TrustSegment { trust, text: content.to_string(), position }),
+                }
+            }
+        }
+        position += len;
+    }
+
+    if let Some((marker, start)) = zones.last() {
+        return Err(mislabeled(*start, &format!("{} zone is never closed", marker_name(*marker).unwrap_or(""))));
+    }
+
+    Ok(segments)
+}
+
+/// Decode an ASC100 payload and split it into trust segments
+pub fn decode_trust_segments(encoded: &str, version: &Asc100Version) -> Result<Vec<TrustSegment>, Asc100Error> {
+    trust_segments(&version.decode_with(encoded, &ExtensionsStrategy::strict())?)
+}
+
+fn current_trust(zones: &[(u8, usize)]) -> Trust {
+    match zones.last() {
+        Some((MARKER_DNT, _)) => Trust::Untrusted,
+        Some(_) => Trust::Trusted,
+        None => Trust::Unlabeled,
+    }
+}
+
+fn mislabeled(position: usize, reason: &str) -> Asc100Error {
+    Asc100Error::MislabeledTrust { position, reason: reason.to_string() }
+}
+
+/// What to do with a span of a given trust level
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrustAction {
+    Keep,
+    Refuse,
+    Redact(String),
+}
+
+/// Policy applied to trust segments before content reaches templates or dispatch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustPolicy {
+    pub untrusted: TrustAction,
+    pub unlabeled: TrustAction,
+}
+
+impl TrustPolicy {
+    /// Fail on any untrusted span
+    pub fn refuse_untrusted() -> Self {
+        Self { untrusted: TrustAction::Refuse, unlabeled: TrustAction::Keep }
+    }
+    
+    /// Replace untrusted spans with the given text
+    pub fn redact_untrusted(replacement: &str) -> Self {
+        Self { untrusted: TrustAction::Redact(replacement.to_string()), unlabeled: TrustAction::Keep }
+    }
+    
+    /// Accept only content explicitly labeled `#TR#`
+    pub fn trusted_only() -> Self {
+        Self { untrusted: TrustAction::Refuse, unlabeled: TrustAction::Refuse }
+    }
+    
+    /// Apply the policy, joining the surviving spans back into text
+    pub fn apply(&self, segments: &[TrustSegment]) -> Result<String, Asc100Error> {
+        let mut result = String::new();
+        for segment in segments {
+            let action = match segment.trust {
+                Trust::Trusted => &TrustAction::Keep,
+                Trust::Untrusted => &self.untrusted,
+                Trust::Unlabeled => &self.unlabeled,
+            };
+            match action {
+                TrustAction::Keep => result.push_str(&segment.text),
+                TrustAction::Redact(replacement) => result.push_str(replacement),
+                TrustAction::Refuse => {
+                    return Err(Asc100Error::UntrustedContent {
+                        trust: segment.trust,
+                        position: segment.position,
+                    });
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Decode an ASC100 payload, keeping only what the policy lets through
+pub fn decode_trusted(encoded: &str, version: &Asc100Version, policy: &TrustPolicy) -> Result<String, Asc100Error> {
+    policy.apply(&decode_trust_segments(encoded, version)?)
+}
+
+impl Template {
+    /// Decode and parse a template after applying a trust policy
+    pub fn from_trusted(encoded: &str, version: &Asc100Version, policy: &TrustPolicy) -> Result<Self, Asc100Error> {
+        Self::parse(&decode_trusted(encoded, version, policy)?)
+    }
+}
+
+impl Dispatcher {
+    /// Decode a payload, apply a trust policy, then run every call in it
+    pub fn dispatch_trusted(
+        &self,
+        encoded: &str,
+        version: &Asc100Version,
+        policy: &TrustPolicy
+    ) -> Result<Vec<String>, Asc100Error> {
+        parse_calls(&decode_trusted(encoded, version, policy)?)?
+            .iter()
+            .map(|call| self.dispatch(call))
+            .collect()
+    }
+}
@@ -0,0 +1,150 @@
+//! Cross-version transcoding on the 7-bit index stream
+//!
+//! Payloads are moved between charset versions by mapping each index through
+//! a precomputed permutation, without rebuilding the plaintext. Marker indices
+//! (100-127) map to themselves, except that the source version's control
+//! marker and the target's trade places, so a version stamp stays valid.
+//! Shift-coded payloads are charset-independent, so only their markers change.
+
+use std::io::{self, Write};
+
+use crate::char::versions::{Asc100Version, V1_STANDARD};
+use crate::char::{BASE64_CHARS, BASE64_LOOKUP};
+use crate::{pack_indices, shift, varlen, Asc100Error};
+
+/// Base64 characters holding a whole number of indices (42 bits = 6 indices)
+const BLOCK_CHARS: usize = 7;
+
+/// Index-to-index mapping from one version's charset to another's
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permutation {
+    table: [u8; 128],
+}
+
+impl Permutation {
+    pub fn new(from: &Asc100Version, to: &Asc100Version) -> Result<Self, Asc100Error> {
+        let mut table = [0u8; 128];
+        for (index, slot) in table.iter_mut().enumerate() {
+            *slot = index as u8;
+        }
+        for (index, &ch) in from.charset.iter().enumerate() {
+            let target = to.lookup.get(ch as usize).copied().unwrap_or(255);
+            if target == 255 {
+                return Err(Asc100Error::InvalidCharacter(ch));
+            }
+            table[index] = target;
+        }
+        table.swap(from.control_marker as usize, to.control_marker as usize);
+        Ok(Self { table })
+    }
+
+    pub fn apply(&self, index: u8) -> u8 {
+        self.table[(index & 0x7f) as usize]
+    }
+
+    /// Whether the mapping changes nothing (e.g. identical charsets)
+    pub fn is_identity(&self) -> bool {
+        self.table.iter().enumerate().all(|(index, &target)| index == target as usize)
+    }
+
+    /// Transcode one payload, keeping its packing
+    pub fn transcode(&self, encoded: &str) -> Result<String, Asc100Error> {
+        if let Some(body) = encoded.strip_prefix(shift::SHIFT_TAG) {
+            // Any charset works as long as both sides use the same one
+            let indices = shift::unpack(body, &V1_STANDARD.charset, &BASE64_LOOKUP)?
+                .into_iter()
+                .map(|index| if index >= 100 { self.apply(index) } else { index })
+                .collect::<Vec<_>>();
+            return Ok(shift::pack(&indices, &V1_STANDARD.charset, &BASE64_CHARS));
+        }
+        if let Some(body) = encoded.strip_prefix(varlen::VARIABLE_TAG) {
+            return Ok(varlen::pack(&self.apply_all(varlen::unpack(body, &BASE64_LOOKUP)?), &BASE64_CHARS));
+        }
+        Ok(pack_indices(&self.apply_all(crate::unpack_fixed(encoded, &BASE64_LOOKUP)?), &BASE64_CHARS))
+    }
+
+    fn apply_all(&self, indices: Vec<u8>) -> Vec<u8> {
+        indices.into_iter().map(|index| self.apply(index)).collect()
+    }
+}
+
+/// Re-encode a payload from one version to another
+pub fn transcode(encoded: &str, from: &Asc100Version, to: &Asc100Version) -> Result<String, Asc100Error> {
+    Permutation::new(from, to)?.transcode(encoded)
+}
+
+/// Streaming transcoder for bulk migrations
+///
+/// Payload bytes written to it are transcoded in 7-character blocks and passed
+/// to the inner writer. Line breaks end the current payload and are copied
+/// through, so a file with one payload per line can be piped through as-is.
+/// Tagged payloads have no block boundaries and are buffered until their
+/// line ends. Call `finish` to flush the last payload.
+pub struct TranscodeWriter<W: Write> {
+    inner: W,
+    permutation: Permutation,
+    pending: Vec<u8>,
+    /// Whether part of the current payload has already been written
+    started: bool,
+}
+
+impl<W: Write> TranscodeWriter<W> {
+    pub fn new(inner: W, from: &Asc100Version, to: &Asc100Version) -> Result<Self, Asc100Error> {
+        Ok(Self { inner, permutation: Permutation::new(from, to)?, pending: Vec::with_capacity(BLOCK_CHARS), started: false })
+    }
+
+    /// Flush the last payload and return the inner writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.flush_payload()?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    fn flush_payload(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        // Pending bytes were checked against the payload alphabet on write
+        let block = std::str::from_utf8(&self.pending).unwrap_or("");
+        let transcoded = self.permutation.transcode(block).map_err(invalid_data)?;
+        self.pending.clear();
+        self.started = true;
+        self.inner.write_all(transcoded.as_bytes())
+    }
+}
+
+impl<W: Write> Write for TranscodeWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &byte in buf {
+            match byte {
+                b'\n' | b'\r' => {
+                    self.flush_payload()?;
+                    self.started = false;
+                    self.inner.write_all(&[byte])?;
+                }
+                _ if is_tag(byte) && self.pending.is_empty() && !self.started => self.pending.push(byte),
+                _ if byte < 128 && BASE64_LOOKUP[byte as usize] != 255 => {
+                    self.pending.push(byte);
+                    if self.pending.len() == BLOCK_CHARS && !is_tag(self.pending[0]) {
+                        self.flush_payload()?;
+                    }
+                }
+                _ => return Err(invalid_data(Asc100Error::InvalidBase64Character(byte as char))),
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // A partial block can only be written once the payload ends
+        self.inner.flush()
+    }
+}
+
+fn is_tag(byte: u8) -> bool {
+    byte == varlen::VARIABLE_TAG as u8 || byte == shift::SHIFT_TAG as u8
+}
+
+fn invalid_data(error: Asc100Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
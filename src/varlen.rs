@@ -0,0 +1,126 @@
+//! Variable-length index coding
+//!
+//! A prefix code where low indices get short codes, so a version that puts
+//! frequent characters first actually produces smaller payloads:
+//!
+//! | Indices  | Code            | Bits |
+//! |----------|-----------------|------|
+//! | 0-15     | `0` + 4 bits    | 5    |
+//! | 16-47    | `10` + 5 bits   | 7    |
+//! | 48-111   | `110` + 6 bits  | 9    |
+//! | 112-127  | `111` + 4 bits  | 7    |
+//!
+//! Variable-length payloads start with the `~` format tag, which is not in the
+//! payload alphabet, so they can never be mistaken for a fixed-width stream.
+//! The final Base64 character is padded with `1` bits, which never form a
+//! complete code.
+
+use crate::Asc100Error;
+
+/// Prefix marking a variable-length payload
+pub const VARIABLE_TAG: char = '~';
+
+/// Number of bits used for an index
+pub const fn code_length(index: u8) -> usize {
+    match index {
+        0..=15 => 5,
+        16..=47 => 7,
+        48..=111 => 9,
+        _ => 7,
+    }
+}
+
+/// Whether a payload carries the variable-length format tag
+pub fn is_variable(encoded: &str) -> bool {
+    encoded.starts_with(VARIABLE_TAG)
+}
+
+/// Pack indices into a tagged variable-length payload
+pub(crate) fn pack(indices: &[u8], alphabet: &[char; 64]) -> String {
+    let mut bits = Vec::with_capacity(indices.len() * 7);
+    for &index in indices {
+        let (prefix, prefix_len, value, value_len) = match index {
+            0..=15 => (0b0, 1, index, 4),
+            16..=47 => (0b10, 2, index - 16, 5),
+            48..=111 => (0b110, 3, index - 48, 6),
+            _ => (0b111, 3, (index - 112) & 0x0f, 4),
+        };
+        push_bits(&mut bits, prefix, prefix_len);
+        push_bits(&mut bits, value, value_len);
+    }
+
+    // Pad with 1 bits, which never complete a code
+    while bits.len() % 6 != 0 {
+        bits.push(1);
+    }
+
+    let mut result = String::with_capacity(bits.len() / 6 + 1);
+    result.push(VARIABLE_TAG);
+    for chunk in bits.chunks(6) {
+        let mut value = 0u8;
+        for (i, &bit) in chunk.iter().enumerate() {
+            value |= bit << (5 - i);
+        }
+        result.push(alphabet[value as usize]);
+    }
+    result
+}
+
+/// Unpack a variable-length payload body (without the tag)
+pub(crate) fn unpack(body: &str, alphabet_lookup: &[u8; 128]) -> Result<Vec<u8>, Asc100Error> {
+    let bits = to_bits(body, alphabet_lookup)?;
+    Ok(decode_bits(&bits).0)
+}
+
+/// Why a variable-length payload body is not canonical, if it isn't
+pub(crate) fn padding_issue(body: &str, alphabet_lookup: &[u8; 128]) -> Option<&'static str> {
+    let bits = to_bits(body, alphabet_lookup).ok()?;
+    let (_, consumed) = decode_bits(&bits);
+    let padding = &bits[consumed..];
+    if padding.len() >= 6 {
+        Some("trailing character carries no index")
+    } else if padding.contains(&0) {
+        Some("padding bits are not one")
+    } else {
+        None
+    }
+}
+
+fn push_bits(bits: &mut Vec<u8>, value: u8, len: usize) {
+    for i in (0..len).rev() {
+        bits.push((value >> i) & 1);
+    }
+}
+
+fn to_bits(body: &str, alphabet_lookup: &[u8; 128]) -> Result<Vec<u8>, Asc100Error> {
+    let mut bits = Vec::with_capacity(body.len() * 6);
+    for ch in body.chars() {
+        let value = if (ch as u32) < 128 { alphabet_lookup[ch as usize] } else { 255 };
+        if value == 255 {
+            return Err(Asc100Error::InvalidBase64Character(ch));
+        }
+        push_bits(&mut bits, value, 6);
+    }
+    Ok(bits)
+}
+
+/// Decode complete codes, returning the indices and the number of bits used
+fn decode_bits(bits: &[u8]) -> (Vec<u8>, usize) {
+    let read = |from: usize, len: usize| bits[from..from + len].iter().fold(0u8, |acc, &bit| (acc << 1) | bit);
+
+    let mut indices = Vec::new();
+    let mut pos = 0;
+    loop {
+        let remaining = bits.len() - pos;
+        let (len, index) = match bits.get(pos..pos + 3.min(remaining)) {
+            Some([0, ..]) if remaining >= 5 => (5, read(pos + 1, 4)),
+            Some([1, 0, ..]) if remaining >= 7 => (7, 16 + read(pos + 2, 5)),
+            Some([1, 1, 0]) if remaining >= 9 => (9, 48 + read(pos + 3, 6)),
+            Some([1, 1, 1]) if remaining >= 7 => (7, 112 + read(pos + 3, 4)),
+            _ => break,
+        };
+        indices.push(index);
+        pos += len;
+    }
+    (indices, pos)
+}
@@ -0,0 +1,102 @@
+use asc100::char::versions::V1_STANDARD;
+use asc100::char::extensions::{CoreStrategy, ExtensionsStrategy};
+use asc100::proto::{Part, Reassembler, Splitter, MAX_PARTS};
+use asc100::Asc100Error;
+
+const LONG_TEXT: &str = "The quick brown fox jumps over the lazy dog. #V#name#V# \
+    Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor.";
+
+#[test]
+fn test_parts_fit_budget_and_reassemble_in_any_order() {
+    let parts = Splitter::new(&V1_STANDARD, 40).split(LONG_TEXT).unwrap();
+    assert!(parts.len() > 3);
+    assert!(parts.iter().all(|part| part.to_string().len() <= 40));
+
+    let mut reassembler = Reassembler::new(&V1_STANDARD);
+    let mut result = None;
+    for part in parts.iter().rev() {
+        assert!(result.is_none());
+        result = reassembler.push(&part.to_string()).unwrap();
+    }
+    assert_eq!(result.as_deref(), Some(LONG_TEXT));
+    assert!(reassembler.pending_ids().is_empty());
+}
+
+#[test]
+fn test_missing_and_duplicate_parts() {
+    let parts = Splitter::new(&V1_STANDARD, 30).with_message_id("msg7").unwrap().split(LONG_TEXT).unwrap();
+    let total = parts.len();
+
+    let mut reassembler = Reassembler::new(&V1_STANDARD);
+    reassembler.push(&parts[0].to_string()).unwrap();
+    reassembler.push(&parts[2].to_string()).unwrap();
+
+    let missing = reassembler.missing("msg7");
+    assert_eq!(missing[0], 2);
+    assert_eq!(missing.len(), total - 2);
+
+    match reassembler.push(&parts[2].to_string()) {
+        Err(Asc100Error::DuplicatePart { message_id, index }) => {
+            assert_eq!(message_id, "msg7");
+            assert_eq!(index, 3);
+        }
+        other => panic!("Expected duplicate part, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_part_format_and_validation() {
+    let parts = Splitter::new(&V1_STANDARD, 100).with_message_id("id1").unwrap().split("short").unwrap();
+    assert_eq!(parts.len(), 1);
+    assert!(parts[0].to_string().starts_with("id1:1:1:"));
+    assert_eq!(Part::parse(&parts[0].to_string()).unwrap(), parts[0]);
+
+    assert!(matches!(Part::parse("id:0:1:abc"), Err(Asc100Error::InvalidPart(_))));
+    assert!(matches!(Part::parse("id:3:2:abc"), Err(Asc100Error::InvalidPart(_))));
+    assert!(matches!(Part::parse("no header"), Err(Asc100Error::InvalidPart(_))));
+    assert!(matches!(
+        Splitter::new(&V1_STANDARD, 10).split(LONG_TEXT),
+        Err(Asc100Error::ChunkBudgetTooSmall(10))
+    ));
+}
+
+#[test]
+fn test_empty_message() {
+    let parts = Splitter::new(&V1_STANDARD, 20).split("").unwrap();
+    assert_eq!(parts.len(), 1);
+    let mut reassembler = Reassembler::new(&V1_STANDARD);
+    assert_eq!(reassembler.push(&parts[0].to_string()).unwrap().as_deref(), Some(""));
+}
+
+#[test]
+fn test_untrusted_parts_are_bounded() {
+    assert!(matches!(Part::parse(&format!("id:1:{}:abc", MAX_PARTS + 1)), Err(Asc100Error::InvalidPart(_))));
+    assert!(matches!(Part::parse("a:b:1:1:abc"), Err(Asc100Error::InvalidPart(_))));
+    assert!(matches!(Splitter::new(&V1_STANDARD, 40).with_message_id("a:b"), Err(Asc100Error::InvalidMessageId(_))));
+    assert!(matches!(Splitter::new(&V1_STANDARD, 40).with_message_id(""), Err(Asc100Error::InvalidMessageId(_))));
+
+    let mut reassembler = Reassembler::new(&V1_STANDARD).with_max_pending(2);
+    reassembler.push("m1:1:2:AAAA").unwrap();
+    reassembler.push("m2:1:2:AAAA").unwrap();
+    assert!(matches!(reassembler.push("m3:1:2:AAAA"), Err(Asc100Error::TooManyPendingMessages(2))));
+    // Parts of messages already pending are still accepted
+    assert!(reassembler.push("m2:2:2:AAAA").is_ok());
+    assert!(reassembler.push("m3:1:2:AAAA").is_ok());
+}
+
+#[test]
+fn test_failed_decode_keeps_parts() {
+    let parts = Splitter::new(&V1_STANDARD, 40).with_message_id("m").unwrap().split("#V#name#V#").unwrap();
+    let mut reassembler = Reassembler::new(&V1_STANDARD);
+    for part in &parts[..parts.len() - 1] {
+        assert_eq!(reassembler.push(&part.to_string()).unwrap(), None);
+    }
+    let last = parts.last().unwrap().clone();
+    assert!(reassembler.push_with(last, &CoreStrategy::strict()).is_err());
+    assert_eq!(reassembler.pending_ids(), vec!["m"]);
+    assert!(reassembler.missing("m").is_empty());
+
+    assert_eq!(reassembler.retry_with("m", &ExtensionsStrategy::strict()).unwrap().as_deref(), Some("#V#name#V#"));
+    assert!(reassembler.pending_ids().is_empty());
+    assert!(!reassembler.discard("m"));
+}
@@ -0,0 +1,103 @@
+use asc100::char::extensions::{ExtensionsStrategy, VersionedStrategy};
+use asc100::char::registry::strategy_by_name;
+use asc100::char::versions::{V1_STANDARD, V4_URL};
+use asc100::codec::Codec;
+use asc100::Asc100Error;
+
+const URL_SAFE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+#[test]
+fn test_default_codec_matches_version_api() {
+    let input = "Hello, World! 123";
+    let codec = Codec::new(V4_URL);
+    let encoded = codec.encode(input).unwrap();
+    assert_eq!(encoded, V4_URL.encode(input).unwrap());
+    assert_eq!(encoded, V4_URL.encode_with(input, &asc100::char::extensions::CoreStrategy::strict()).unwrap());
+    assert_eq!(codec.decode(&encoded).unwrap(), input);
+}
+
+#[test]
+fn test_builder_options() {
+    let input = "https://example.com/a/long/path?query=value&other=thing#V#".repeat(3);
+    let codec = Codec::builder()
+        .version(V4_URL)
+        .strategy(VersionedStrategy::new(ExtensionsStrategy::strict(), &V4_URL))
+        .alphabet(URL_SAFE)
+        .wrap(20)
+        .canonical(true)
+        .build()
+        .unwrap();
+
+    let encoded = codec.encode(&input).unwrap();
+    assert!(encoded.lines().all(|line| line.len() <= 20));
+    assert!(encoded.lines().count() > 1);
+    assert!(!encoded.contains(['+', '/']));
+    assert_eq!(codec.decode(&encoded).unwrap(), input);
+
+    let mut out = String::from("payload=");
+    codec.encode_into("x", &mut out).unwrap();
+    assert!(out.starts_with("payload=") && out.len() > "payload=".len());
+}
+
+#[test]
+fn test_codec_is_clone_send_sync() {
+    fn assert_send_sync<T: Send + Sync + Clone>(_: &T) {}
+
+    let codec = Codec::builder().strategy(strategy_by_name("extensions+sanitize").unwrap()).build().unwrap();
+    assert_send_sync(&codec);
+
+    let shared = codec.clone();
+    let handle = std::thread::spawn(move || shared.encode("café #V#").unwrap());
+    let encoded = handle.join().unwrap();
+    assert_eq!(codec.decode(&encoded).unwrap(), "caf#INV# #V#");
+}
+
+#[test]
+fn test_canonical_rejects_non_canonical_payloads() {
+    let lenient = Codec::new(V1_STANDARD);
+    let strict = Codec::builder().canonical(true).build().unwrap();
+    let encoded = strict.encode("abc").unwrap();
+
+    // "abc" is 21 bits in 4 characters, leaving 3 padding bits
+    let alphabet: Vec<char> = asc100::char::BASE64_CHARS.to_vec();
+    let mut dirty = encoded.clone();
+    let last = dirty.pop().unwrap();
+    let value = alphabet.iter().position(|&ch| ch == last).unwrap();
+    dirty.push(alphabet[value | 1]);
+    assert_eq!(lenient.decode(&dirty).unwrap(), "abc");
+    assert!(matches!(strict.decode(&dirty), Err(Asc100Error::NonCanonicalPayload(_))));
+
+    // Six indices fill seven characters exactly; an eighth carries no index
+    let full = strict.encode("abcdef").unwrap();
+    assert_eq!(full.len(), 7);
+    let extended = format!("{}A", full);
+    assert_eq!(lenient.decode(&extended).unwrap(), "abcdef");
+    assert!(matches!(strict.decode(&extended), Err(Asc100Error::NonCanonicalPayload(_))));
+}
+
+#[test]
+fn test_invalid_alphabets() {
+    let reserved = ['#', ':', ';', '='].map(|ch| URL_SAFE.replace('_', &ch.to_string()));
+    let invalid = ["short".to_string(), URL_SAFE.replace('_', "-"), URL_SAFE.replace('_', " ")];
+    for alphabet in invalid.iter().chain(&reserved) {
+        assert!(matches!(
+            Codec::builder().alphabet(alphabet).build(),
+            Err(Asc100Error::InvalidAlphabet(_))
+        ));
+    }
+}
+
+#[test]
+fn test_entry_points_share_codec_behavior() {
+    let strategy = ExtensionsStrategy::strict();
+    let input = "#V#name#V# and text";
+    let codec = Codec::builder().version(V1_STANDARD).strategy(ExtensionsStrategy::strict()).wrap(8).build().unwrap();
+    let wrapped = codec.encode(input).unwrap();
+    let encoded = wrapped.replace('\n', "");
+
+    assert_eq!(V1_STANDARD.encode_with(input, &strategy).unwrap(), encoded);
+    assert_eq!(asc100::encode_with_strategy(input, &V1_STANDARD.charset, &V1_STANDARD.lookup, &strategy).unwrap(), encoded);
+    // Decoding through any entry point ignores line breaks like the codec does
+    assert_eq!(V1_STANDARD.decode_with(&wrapped, &strategy).unwrap(), input);
+    assert_eq!(asc100::decode_with_strategy(&wrapped, &V1_STANDARD.charset, &strategy).unwrap(), input);
+}
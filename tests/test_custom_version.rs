@@ -0,0 +1,94 @@
+use asc100::char::charset::{create_base_charset, swap_chars, MARKER_DICT, MARKER_EOF, MARKER_NUM, MARKER_V, MARKER_X_V2};
+use asc100::char::extensions::{ExtensionsStrategy, VersionedStrategy};
+use asc100::char::versions::{Asc100Version, CONTROL_MARKERS, CUSTOM_CONTROL_MARKER, V1_STANDARD};
+use asc100::Asc100Error;
+
+fn reversed_charset() -> [char; 100] {
+    let mut charset = create_base_charset();
+    charset.reverse();
+    charset
+}
+
+#[test]
+fn test_custom_version_roundtrip() {
+    let version = Asc100Version::custom("reversed", reversed_charset()).unwrap();
+    assert_eq!(version.name, "reversed");
+    assert_eq!(version.lookup['\x01' as usize], 0);
+
+    let input = "Custom ordering\twith whitespace\n~";
+    let encoded = version.encode(input).unwrap();
+    assert_ne!(encoded, V1_STANDARD.encode(input).unwrap());
+    assert_eq!(version.decode(&encoded).unwrap(), input);
+}
+
+#[test]
+fn test_builtin_orderings_are_valid_custom_charsets() {
+    let version = Asc100Version::custom("v1_copy", V1_STANDARD.charset).unwrap();
+    assert_eq!(version.lookup, V1_STANDARD.lookup);
+    assert_eq!(version.encode("same").unwrap(), V1_STANDARD.encode("same").unwrap());
+}
+
+#[test]
+fn test_invalid_charsets() {
+    // 'b' twice means 'a' is missing
+    let mut duplicated = swap_chars(create_base_charset(), 65, 66);
+    duplicated[66] = 'b';
+    match Asc100Version::custom("dup", duplicated) {
+        Err(Asc100Error::InvalidCharset { duplicates, missing, unexpected }) => {
+            assert_eq!(duplicates, vec!['b']);
+            assert_eq!(missing, vec!['a']);
+            assert!(unexpected.is_empty());
+        }
+        _ => panic!("Expected invalid charset"),
+    }
+
+    let mut foreign = create_base_charset();
+    foreign[99] = 'é';
+    match Asc100Version::custom("foreign", foreign) {
+        Err(Asc100Error::InvalidCharset { missing, unexpected, .. }) => {
+            assert_eq!(missing, vec!['\x01']);
+            assert_eq!(unexpected, vec!['é']);
+        }
+        _ => panic!("Expected invalid charset"),
+    }
+}
+
+#[test]
+fn test_custom_control_marker() {
+    let version = Asc100Version::custom("reversed", reversed_charset()).unwrap()
+        .with_control_marker(MARKER_X_V2)
+        .unwrap();
+    let strategy = VersionedStrategy::new(ExtensionsStrategy::strict(), &version);
+    let encoded = version.encode_with("stamped", &strategy).unwrap();
+    assert_eq!(version.decode_with(&encoded, &strategy).unwrap(), "stamped");
+
+    let v1_strategy = VersionedStrategy::new(ExtensionsStrategy::strict(), &V1_STANDARD);
+    assert!(version.decode_with(&encoded, &v1_strategy).is_err());
+}
+
+#[test]
+fn test_runtime_name_and_default_marker() {
+    let name = format!("tenant_{}", 42);
+    let version = Asc100Version::custom_owned(name.clone(), reversed_charset()).unwrap();
+    assert_eq!(version.name, name);
+    assert_eq!(version.control_marker, CUSTOM_CONTROL_MARKER);
+    let again = Asc100Version::custom_owned(name, reversed_charset()).unwrap();
+    assert!(std::ptr::eq(version.name, again.name));
+
+    // Stamps from a version sharing V1's ordering but not its marker are told apart
+    let renamed = Asc100Version::custom("v1_renamed", V1_STANDARD.charset).unwrap();
+    let encoded = renamed.encode_with("x", &VersionedStrategy::new(ExtensionsStrategy::strict(), &renamed)).unwrap();
+    let v1_strategy = VersionedStrategy::new(ExtensionsStrategy::strict(), &V1_STANDARD);
+    assert!(V1_STANDARD.decode_with(&encoded, &v1_strategy).is_err());
+}
+
+#[test]
+fn test_control_marker_must_be_free() {
+    let version = Asc100Version::custom("reversed", reversed_charset()).unwrap();
+    for marker in [0, 99, MARKER_EOF, MARKER_V, MARKER_NUM, MARKER_DICT, 128] {
+        assert!(matches!(version.with_control_marker(marker), Err(Asc100Error::InvalidControlMarker(m)) if m == marker));
+    }
+    for marker in CONTROL_MARKERS {
+        assert_eq!(version.with_control_marker(marker).unwrap().control_marker, marker);
+    }
+}
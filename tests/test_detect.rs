@@ -0,0 +1,80 @@
+use asc100::char::extensions::ExtensionsStrategy;
+use asc100::char::versions::{Packing, V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL};
+use asc100::detect::{detect, detect_with};
+
+const PROSE: &str = "The quick brown fox jumps over the lazy dog, and then it was time for the test.";
+
+#[test]
+fn test_detects_version_of_prose() {
+    for version in [V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL] {
+        let encoded = version.encode(PROSE).unwrap();
+        let ranked = detect(&encoded);
+        assert!(!ranked.is_empty());
+        assert_eq!(ranked[0].decoded, PROSE);
+
+        // V3 and V4 only differ on characters this text doesn't use, so they tie
+        let own = ranked.iter().find(|detection| detection.version.name == version.name).unwrap();
+        assert_eq!(own.confidence, ranked[0].confidence);
+        assert!(ranked[0].confidence > 0.8, "{:?}", ranked[0].confidence);
+        assert!(ranked.windows(2).all(|pair| pair[0].confidence >= pair[1].confidence));
+    }
+}
+
+#[test]
+fn test_markers_select_extensions_strategy() {
+    let input = "#FX#greet#ARG#hello world#ARG#this is a test";
+    let encoded = V3_LOWERCASE.encode_with(input, &ExtensionsStrategy::strict()).unwrap();
+    let ranked = detect(&encoded);
+
+    // Core cannot decode marker indices at all
+    assert!(ranked.iter().all(|detection| detection.strategy == "extensions"));
+    assert_eq!(ranked[0].version.name, V3_LOWERCASE.name);
+    assert_eq!(ranked[0].decoded, input);
+}
+
+#[test]
+fn test_candidate_lists_and_garbage() {
+    let encoded = V2_NUMBERS.encode(PROSE).unwrap();
+    let ranked = detect_with(&encoded, &[V1_STANDARD, V2_NUMBERS], &["core"]);
+    assert_eq!(ranked.len(), 2);
+    assert_eq!(ranked[0].version.name, V2_NUMBERS.name);
+    assert!(ranked[0].confidence > ranked[1].confidence);
+
+    assert!(detect("not base64 at all!").is_empty());
+    assert!(detect_with(&encoded, &[V1_STANDARD], &["no-such-strategy"]).is_empty());
+}
+
+#[test]
+fn test_non_canonical_padding_lowers_confidence() {
+    let encoded = V1_STANDARD.encode(PROSE).unwrap();
+    let padded = format!("{}A", encoded);
+    let clean = detect(&encoded)[0].confidence;
+    let noisy = detect(&padded)
+        .into_iter()
+        .find(|detection| detection.version.name == V1_STANDARD.name)
+        .unwrap()
+        .confidence;
+    assert!(noisy < clean);
+}
+
+#[test]
+fn test_detects_packed_payloads_of_other_versions() {
+    for packed in [V2_NUMBERS.variable(), V4_URL.variable(), V3_LOWERCASE.shifted(), V4_URL.shifted()] {
+        let encoded = packed.encode(PROSE).unwrap();
+        let ranked = detect(&encoded);
+        assert_eq!(ranked[0].decoded, PROSE, "{} {:?}", packed.name, packed.packing);
+
+        let own = ranked.iter().find(|detection| detection.version.name == packed.name).unwrap();
+        assert_eq!(own.confidence, ranked[0].confidence);
+        assert_eq!(own.version.packing, packed.packing);
+        assert!(own.confidence > 0.8, "{:?}", own.confidence);
+    }
+
+    // A trailing latch ('/') decodes to the same text but is not what the encoder writes
+    let encoded = V3_LOWERCASE.shifted().encode(PROSE).unwrap();
+    let clean = detect(&encoded);
+    let noisy = detect(&format!("{}/", encoded));
+    assert_eq!(noisy[0].decoded, PROSE);
+    assert!(noisy[0].confidence < clean[0].confidence);
+    assert_eq!(clean[0].version.packing, Packing::Shift);
+}
@@ -0,0 +1,126 @@
+use asc100::char::charset::MARKER_DICT;
+use asc100::char::dictionary::{Dictionary, DictionaryStrategy, DEFAULT_DICTIONARY_ID, DEFAULT_ENTRIES};
+use asc100::char::extensions::{CoreStrategy, DigitRunStrategy, EncodingStrategy, ExtensionsStrategy};
+use asc100::char::versions::{Asc100Version, V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL};
+use asc100::Asc100Error;
+
+const SAMPLES: &[&str] = &[
+    "",
+    "x",
+    "0",
+    "Hello, World! 123",
+    "the quick brown fox jumps over the lazy dog",
+    "\tTabs\nand\r\nnewlines~",
+    "https://localhost:8080/api/users",
+    "sec:token config:namespace sec:password",
+    "{\"name\": \"value\", \"status\": \"error\", \"data\": null}",
+    "http://www.example.com and https://www.example.org",
+];
+
+/// Round-trip `SAMPLES` through every built-in version in fixed, variable and
+/// shift packing; decoding accepts every packing, so the fixed version decodes
+fn assert_roundtrip_matrix<S: EncodingStrategy>(strategy_for: impl Fn(&Asc100Version) -> S) {
+    for version in [V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL] {
+        let strategy = strategy_for(&version);
+        for packed in [version, version.variable(), version.shifted()] {
+            for &input in SAMPLES {
+                let encoded = packed.encode_with(input, &strategy).unwrap();
+                let decoded = version.decode_with(&encoded, &strategy).unwrap();
+                assert_eq!(decoded, input, "{} {:?} {:?}", version.name, packed.packing, input);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_builtin_roundtrip_all_versions() {
+    assert_roundtrip_matrix(|version| {
+        let strategy = DictionaryStrategy::new(CoreStrategy::strict(), version);
+        assert_eq!(strategy.dictionary_id(), DEFAULT_DICTIONARY_ID);
+        strategy
+    });
+    // Outside digit runs, as the composition rule asks
+    assert_roundtrip_matrix(|version| {
+        DictionaryStrategy::new(DigitRunStrategy::new(CoreStrategy::strict(), version), version)
+    });
+}
+
+#[test]
+fn test_repeated_fragments_shrink() {
+    let strategy = DictionaryStrategy::new(CoreStrategy::strict(), &V4_URL);
+    let input = "https://localhost/a https://localhost/b https://localhost/c";
+    let plain = V4_URL.encode(input).unwrap();
+    let compressed = V4_URL.encode_with(input, &strategy).unwrap();
+    assert!((compressed.len() as f64) < plain.len() as f64 * 0.6, "{} vs {}", compressed.len(), plain.len());
+
+    // Every payload carries the header, even an empty one
+    let indices = strategy.encode_indices(Vec::new()).unwrap();
+    assert_eq!(indices, vec![MARKER_DICT, DEFAULT_DICTIONARY_ID]);
+    // Longest match wins: "https://" rather than "://"
+    let https: Vec<u8> = "https://".bytes().map(|byte| V4_URL.lookup[byte as usize]).collect();
+    assert_eq!(strategy.encode_indices(https).unwrap(), vec![MARKER_DICT, 0, MARKER_DICT, 0]);
+    assert_eq!(DEFAULT_ENTRIES[0], "https://");
+}
+
+#[test]
+fn test_user_dictionary_id_is_recorded() {
+    let dictionary = Dictionary::new(7, &["tenant-", "region:eu-west-", "##"]).unwrap();
+    let user = DictionaryStrategy::new(ExtensionsStrategy::strict(), &V1_STANDARD)
+        .with_dictionary(&dictionary)
+        .unwrap();
+    assert_eq!(user.dictionary_id(), 7);
+
+    let input = "tenant-42 region:eu-west-1 #V#tenant-43#V# ## https://";
+    let encoded = V1_STANDARD.encode_with(input, &user).unwrap();
+    assert_eq!(V1_STANDARD.decode_with(&encoded, &user).unwrap(), input);
+
+    // The default-only strategy can still read built-in payloads but not id 7
+    let builtin = DictionaryStrategy::new(ExtensionsStrategy::strict(), &V1_STANDARD);
+    assert!(matches!(V1_STANDARD.decode_with(&encoded, &builtin), Err(Asc100Error::UnknownDictionary(7))));
+    let default_payload = V1_STANDARD.encode_with(input, &builtin).unwrap();
+    assert_eq!(V1_STANDARD.decode_with(&default_payload, &user).unwrap(), input);
+
+    assert_roundtrip_matrix(|version| {
+        DictionaryStrategy::new(ExtensionsStrategy::strict(), version).with_dictionary(&dictionary).unwrap()
+    });
+}
+
+#[test]
+fn test_invalid_dictionaries() {
+    assert!(Dictionary::new(DEFAULT_DICTIONARY_ID, &["abc"]).is_err());
+    assert!(Dictionary::new(128, &["abc"]).is_err());
+    assert!(Dictionary::new(1, &["a"]).is_err());
+    assert!(Dictionary::new(1, &["abc", "abc"]).is_err());
+    assert!(matches!(Dictionary::new(1, &["abc", "x#V#y"]), Err(Asc100Error::InvalidDictionary(reason)) if reason.contains("#V#")));
+    assert!(Dictionary::new(1, &["#EOF#"]).is_err());
+    assert!(Dictionary::new(1, &["#NOPE#", "#V", "V#"]).is_ok());
+    let too_many: Vec<String> = (0..128).map(|i| format!("entry{}", i)).collect();
+    let too_many: Vec<&str> = too_many.iter().map(String::as_str).collect();
+    assert!(Dictionary::new(1, &too_many).is_err());
+    assert!(Dictionary::new(1, &too_many[..127]).is_ok());
+
+    let outside = Dictionary::new(2, &["café"]).unwrap();
+    assert!(DictionaryStrategy::new(CoreStrategy::strict(), &V1_STANDARD).with_dictionary(&outside).is_err());
+}
+
+#[test]
+fn test_malformed_payloads_and_literal_escape() {
+    let strategy = DictionaryStrategy::new(CoreStrategy::strict(), &V2_NUMBERS);
+    let is_invalid = |result: Result<Vec<u8>, Asc100Error>| matches!(result, Err(Asc100Error::InvalidDictionary(_)));
+    assert!(is_invalid(strategy.decode_indices(vec![])));
+    assert!(is_invalid(strategy.decode_indices(vec![1, 2, 3])));
+    assert!(is_invalid(strategy.decode_indices(vec![MARKER_DICT, 0, MARKER_DICT])));
+    assert!(is_invalid(strategy.decode_indices(vec![MARKER_DICT, 0, MARKER_DICT, 126])));
+
+    // Wrapped around the digit-run strategy, packed bits equal to #DICT# are escaped
+    let inner = DigitRunStrategy::new(CoreStrategy::strict(), &V2_NUMBERS);
+    let stream = vec![MARKER_DICT, 5, 6];
+    let outer = DictionaryStrategy::new(inner, &V2_NUMBERS);
+    let encoded = outer.encode_indices(stream.clone()).unwrap();
+    assert_eq!(&encoded[2..4], &[MARKER_DICT, 127]);
+    assert_eq!(outer.decode_indices(encoded).unwrap(), stream);
+
+    let digits = "sec:2025010112304500 config:1718040000000";
+    let encoded = V2_NUMBERS.encode_with(digits, &outer).unwrap();
+    assert_eq!(V2_NUMBERS.decode_with(&encoded, &outer).unwrap(), digits);
+}
@@ -0,0 +1,108 @@
+use asc100::char::charset::{MARKER_EOF, MARKER_NUM};
+use asc100::char::extensions::{
+    CoreStrategy, DigitRunStrategy, EncodingStrategy, EofStrategy, ExtensionsStrategy, DEFAULT_MIN_DIGIT_RUN,
+};
+use asc100::char::versions::{V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL};
+use asc100::Asc100Error;
+
+const SAMPLES: &[&str] = &[
+    "",
+    "7",
+    "1234",
+    "12345",
+    "123456",
+    "1234567",
+    "id=20250101123045 ts=1718040000000",
+    "order 000000000000000000000042 shipped",
+    "3.14159265358979323846264338327950288419716939937510",
+    "no digits here at all",
+];
+
+#[test]
+fn test_roundtrip_all_versions() {
+    for version in [V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL] {
+        let strategy = DigitRunStrategy::new(CoreStrategy::strict(), &version);
+        for &input in SAMPLES {
+            let encoded = version.encode_with(input, &strategy).unwrap();
+            assert_eq!(version.decode_with(&encoded, &strategy).unwrap(), input, "{} {:?}", version.name, input);
+        }
+        for packed in [version.variable(), version.shifted()] {
+            let encoded = packed.encode_with(SAMPLES[6], &strategy).unwrap();
+            assert_eq!(packed.decode_with(&encoded, &strategy).unwrap(), SAMPLES[6]);
+        }
+    }
+}
+
+#[test]
+fn test_long_runs_shrink_by_half() {
+    let strategy = DigitRunStrategy::new(CoreStrategy::strict(), &V2_NUMBERS);
+    let digits: String = (0..120).map(|i| char::from(b'0' + (i * 7 % 10) as u8)).collect();
+    let plain = V2_NUMBERS.encode(&digits).unwrap();
+    let packed = V2_NUMBERS.encode_with(&digits, &strategy).unwrap();
+    assert!((packed.len() as f64) < plain.len() as f64 * 0.52, "{} vs {}", packed.len(), plain.len());
+
+    // Runs longer than one segment are split
+    let long = digits.repeat(3);
+    let encoded = V2_NUMBERS.encode_with(&long, &strategy).unwrap();
+    assert_eq!(V2_NUMBERS.decode_with(&encoded, &strategy).unwrap(), long);
+}
+
+#[test]
+fn test_short_runs_and_text_unchanged() {
+    let strategy = DigitRunStrategy::new(CoreStrategy::strict(), &V1_STANDARD);
+    let input = "room 101, floor 12, call 555-0199";
+    assert_eq!(
+        V1_STANDARD.encode_with(input, &strategy).unwrap(),
+        V1_STANDARD.encode(input).unwrap()
+    );
+    assert_eq!(DEFAULT_MIN_DIGIT_RUN, 5);
+
+    let eager = DigitRunStrategy::new(CoreStrategy::strict(), &V1_STANDARD).min_run(1);
+    let encoded = V1_STANDARD.encode_with(input, &eager).unwrap();
+    assert_ne!(encoded, V1_STANDARD.encode(input).unwrap());
+    assert_eq!(V1_STANDARD.decode_with(&encoded, &eager).unwrap(), input);
+}
+
+#[test]
+fn test_index_layout() {
+    let strategy = DigitRunStrategy::new(CoreStrategy::strict(), &V2_NUMBERS);
+    // V2 digits are indices 0-9, so the stream for "12345" is easy to follow
+    let indices = strategy.encode_indices(vec![1, 2, 3, 4, 5]).unwrap();
+    // 123 -> 10 bits, 45 -> 7 bits: 17 bits in three 7-bit indices
+    assert_eq!(indices.len(), 2 + 3);
+    assert_eq!(&indices[..2], &[MARKER_NUM, 5]);
+    assert_eq!(strategy.decode_indices(indices).unwrap(), vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn test_malformed_runs_are_rejected() {
+    let strategy = DigitRunStrategy::new(CoreStrategy::strict(), &V2_NUMBERS);
+    let is_malformed = |result: Result<Vec<u8>, Asc100Error>| matches!(result, Err(Asc100Error::MalformedDigitRun(_)));
+    assert!(is_malformed(strategy.decode_indices(vec![MARKER_NUM])));
+    assert!(is_malformed(strategy.decode_indices(vec![MARKER_NUM, 0])));
+    assert!(is_malformed(strategy.decode_indices(vec![MARKER_NUM, 5, 0])));
+    // 1023 is not a valid 3-digit group
+    assert!(is_malformed(strategy.decode_indices(vec![MARKER_NUM, 3, 127, 0b1110000])));
+    // One digit takes 4 bits; the 3 padding bits must be zero
+    assert_eq!(strategy.decode_indices(vec![MARKER_NUM, 1, 0b0001000]).unwrap(), vec![1]);
+    assert!(is_malformed(strategy.decode_indices(vec![MARKER_NUM, 1, 0b0001001])));
+
+    // Wrapped around other strategies, packed bits never reach them
+    let outer = DigitRunStrategy::new(EofStrategy::new(ExtensionsStrategy::strict()).append_eof(), &V1_STANDARD);
+    let input = "#V#1010110101101011#V#";
+    let encoded = V1_STANDARD.encode_with(input, &outer).unwrap();
+    assert_eq!(V1_STANDARD.decode_with(&encoded, &outer).unwrap(), input);
+}
+
+#[test]
+fn test_position_wrappers_compose_outside() {
+    // 808 packs to 0b1100101000: its first 7 bits are index 101, the #EOF# marker
+    let strategy = DigitRunStrategy::new(ExtensionsStrategy::strict(), &V2_NUMBERS);
+    let packed = strategy.encode_indices(vec![8, 0, 8, 0, 0]).unwrap();
+    assert_eq!(packed[2], MARKER_EOF);
+
+    let outer = EofStrategy::new(strategy).append_eof().reject_trailer();
+    let input = "id 80800 end";
+    let encoded = V2_NUMBERS.encode_with(input, &outer).unwrap();
+    assert_eq!(V2_NUMBERS.decode_with(&encoded, &outer).unwrap(), input);
+}
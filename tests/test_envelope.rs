@@ -0,0 +1,64 @@
+use asc100::char::versions::{V1_STANDARD, V4_URL};
+use asc100::proto::Envelope;
+use asc100::Asc100Error;
+
+#[test]
+fn test_envelope_format() {
+    let envelope = Envelope::new("payload")
+        .with_content_type("text/plain")
+        .with_origin("node-1")
+        .with_context("lang", "en")
+        .unwrap();
+
+    assert_eq!(
+        envelope.to_string(),
+        "#MEM#content-type=text/plain#BRK#origin=node-1#MEM##CTX#lang=en#CTX#payload"
+    );
+    assert_eq!(Envelope::parse(&envelope.to_string()).unwrap(), envelope);
+}
+
+#[test]
+fn test_typed_getters() {
+    let envelope = Envelope::new("").with_timestamp(1_700_000_000).with_meta("retries", "3").unwrap();
+    let parsed = Envelope::parse(&envelope.to_string()).unwrap();
+
+    assert_eq!(parsed.timestamp(), Some(1_700_000_000));
+    assert_eq!(parsed.get_as::<u8>("retries"), Some(3));
+    assert_eq!(parsed.get_as::<u8>("missing"), None);
+    assert_eq!(parsed.content_type(), None);
+}
+
+#[test]
+fn test_body_and_values_with_markers_roundtrip() {
+    let envelope = Envelope::new("#SSX#Hello #V#name#V##ESX#")
+        .with_meta("note", "a=b #BRK# \"quoted\"")
+        .and_then(|envelope| envelope.with_context("empty", ""))
+        .unwrap();
+
+    let encoded = envelope.encode(&V4_URL).unwrap();
+    let decoded = Envelope::from_encoded(&encoded, &V4_URL).unwrap();
+    assert_eq!(decoded, envelope);
+    assert_eq!(decoded.get("note"), Some("a=b #BRK# \"quoted\""));
+}
+
+#[test]
+fn test_empty_envelope_and_errors() {
+    let empty = Envelope::new("just body");
+    let encoded = empty.encode(&V1_STANDARD).unwrap();
+    assert_eq!(Envelope::from_encoded(&encoded, &V1_STANDARD).unwrap(), empty);
+
+    assert!(matches!(Envelope::parse("no header"), Err(Asc100Error::MalformedEnvelope(_))));
+    assert!(matches!(Envelope::parse("#MEM#key#MEM##CTX##CTX#"), Err(Asc100Error::MalformedEnvelope(_))));
+    assert!(matches!(Envelope::parse("#MEM#a=1#MEM#"), Err(Asc100Error::MalformedEnvelope(_))));
+}
+
+#[test]
+fn test_keys_with_equals_are_rejected() {
+    assert!(matches!(Envelope::new("").with_meta("a=b", "c"), Err(Asc100Error::MalformedEnvelope(_))));
+    assert!(matches!(Envelope::new("").with_context("=", ""), Err(Asc100Error::MalformedEnvelope(_))));
+
+    // Keys inserted into the public maps are checked before encoding
+    let mut envelope = Envelope::new("body");
+    envelope.context.insert("a=b".to_string(), "c".to_string());
+    assert!(matches!(envelope.encode(&V1_STANDARD), Err(Asc100Error::MalformedEnvelope(_))));
+}
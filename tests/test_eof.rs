@@ -0,0 +1,104 @@
+use asc100::char::extensions::{EofStrategy, ExtensionsStrategy, QuoteSafeStrategy, StrictFilter, VersionedStrategy};
+use asc100::char::versions::{V1_STANDARD, V2_NUMBERS};
+use asc100::codec::Codec;
+use asc100::Asc100Error;
+
+#[test]
+fn test_eof_terminates_decoding() {
+    let encoded = V1_STANDARD.encode_with("body#EOF#junk", &ExtensionsStrategy::strict()).unwrap();
+
+    // Without the EOF strategy the junk comes back
+    let plain = V1_STANDARD.decode_with(&encoded, &ExtensionsStrategy::strict()).unwrap();
+    assert_eq!(plain, "body#EOF#junk");
+
+    let strategy = EofStrategy::new(ExtensionsStrategy::strict());
+    assert_eq!(V1_STANDARD.decode_with(&encoded, &strategy).unwrap(), "body");
+}
+
+#[test]
+fn test_trailer_returned_or_rejected() {
+    let encoded = V1_STANDARD.encode_with("body#EOF#junk#V#", &ExtensionsStrategy::strict()).unwrap();
+
+    let strategy = EofStrategy::new(ExtensionsStrategy::strict());
+    let (body, trailer) = strategy.decode_with_trailer(&encoded, &V1_STANDARD).unwrap();
+    assert_eq!(body, "body");
+    assert_eq!(trailer.as_deref(), Some("junk#V#"));
+
+    let rejecting = EofStrategy::new(ExtensionsStrategy::strict()).reject_trailer();
+    match V1_STANDARD.decode_with(&encoded, &rejecting) {
+        Err(Asc100Error::TrailingContent { position }) => assert_eq!(position, 9),
+        other => panic!("Expected trailing content error, got {:?}", other),
+    }
+
+    // A bare #EOF# at the end is not a trailer
+    let clean = V1_STANDARD.encode_with("body#EOF#", &ExtensionsStrategy::strict()).unwrap();
+    assert_eq!(V1_STANDARD.decode_with(&clean, &rejecting).unwrap(), "body");
+}
+
+#[test]
+fn test_appended_eof_detects_truncation() {
+    let strategy = EofStrategy::new(ExtensionsStrategy::strict()).append_eof().reject_trailer();
+    let input = "a complete message";
+    let encoded = V1_STANDARD.encode_with(input, &strategy).unwrap();
+    assert_eq!(V1_STANDARD.decode_with(&encoded, &strategy).unwrap(), input);
+
+    let plain = V1_STANDARD.decode_with(&encoded, &ExtensionsStrategy::strict()).unwrap();
+    assert_eq!(plain, "a complete message#EOF#");
+
+    let truncated = &encoded[..encoded.len() - 2];
+    assert!(matches!(V1_STANDARD.decode_with(truncated, &strategy), Err(Asc100Error::MissingEof)));
+    let (_, trailer) = strategy.decode_with_trailer(&encoded, &V1_STANDARD).unwrap();
+    assert_eq!(trailer.as_deref(), Some(""));
+}
+
+#[test]
+fn test_explicit_eof_is_not_doubled() {
+    let strategy = EofStrategy::new(ExtensionsStrategy::strict()).append_eof();
+    let with_marker = V1_STANDARD.encode_with("done#EOF#", &strategy).unwrap();
+    let without = V1_STANDARD.encode_with("done", &strategy).unwrap();
+    assert_eq!(with_marker, without);
+}
+
+#[test]
+fn test_inner_strategy_sees_whole_stream() {
+    // A suffix stamp stays after the trailer and is still checked
+    let stamped = || VersionedStrategy::new(ExtensionsStrategy::strict(), &V2_NUMBERS).suffix();
+    let encoded = V2_NUMBERS.encode_with("body#EOF#junk", &stamped()).unwrap();
+    let strategy = EofStrategy::new(stamped());
+    assert_eq!(V2_NUMBERS.decode_with(&encoded, &strategy).unwrap(), "body");
+    let (body, trailer) = strategy.decode_with_trailer(&encoded, &V2_NUMBERS).unwrap();
+    assert_eq!((body.as_str(), trailer.as_deref()), ("body", Some("junk")));
+
+    let appending = EofStrategy::new(stamped()).append_eof();
+    let encoded = V2_NUMBERS.encode_with("body", &appending).unwrap();
+    assert_eq!(V2_NUMBERS.decode_with(&encoded, &appending).unwrap(), "body");
+    assert_eq!(V2_NUMBERS.decode_with(&encoded, &stamped()).unwrap(), "body#EOF#");
+
+    // The trailer is restored by the inner strategy like the body
+    let quoted = EofStrategy::new(QuoteSafeStrategy::new(StrictFilter, &V1_STANDARD));
+    let encoded = V1_STANDARD.encode_with("it's#EOF#'\\", &quoted).unwrap();
+    let (body, trailer) = quoted.decode_with_trailer(&encoded, &V1_STANDARD).unwrap();
+    assert_eq!((body.as_str(), trailer.as_deref()), ("it's", Some("'\\")));
+}
+
+#[test]
+fn test_trailer_decoding_shares_the_codec_path() {
+    let strategy = EofStrategy::new(ExtensionsStrategy::strict());
+    let codec = Codec::builder().strategy(ExtensionsStrategy::strict()).wrap(8).canonical(true).build().unwrap();
+    let wrapped = codec.encode("a longer body that wraps#EOF#and its trailer").unwrap();
+    assert!(wrapped.contains('\n'));
+
+    let (body, trailer) = strategy.decode_with_trailer(&wrapped, &V1_STANDARD).unwrap();
+    assert_eq!((body.as_str(), trailer.as_deref()), ("a longer body that wraps", Some("and its trailer")));
+    assert_eq!(strategy.decode_with_trailer_in(&wrapped, &codec).unwrap().0, body);
+
+    // 9 indices leave three padding bits in the last character; setting one is non-canonical
+    let encoded = V1_STANDARD.encode_with("body#EOF#junk", &ExtensionsStrategy::strict()).unwrap();
+    let last = encoded.chars().last().unwrap();
+    let tampered = format!("{}{}", &encoded[..encoded.len() - 1], (last as u8 + 1) as char);
+    assert!(strategy.decode_with_trailer(&tampered, &V1_STANDARD).is_ok());
+    assert!(matches!(
+        strategy.decode_with_trailer_in(&tampered, &codec),
+        Err(Asc100Error::NonCanonicalPayload(_))
+    ));
+}
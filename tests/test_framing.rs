@@ -0,0 +1,70 @@
+use asc100::char::versions::{V1_STANDARD, V3_LOWERCASE};
+use asc100::char::extensions::CoreStrategy;
+use asc100::proto::{Frame, FrameReader, FrameWriter};
+use asc100::Asc100Error;
+
+fn write_frames(messages: &[&str]) -> Vec<u8> {
+    let mut writer = FrameWriter::new(Vec::new(), &V1_STANDARD).sequenced().with_length();
+    for message in messages {
+        writer.write_message(message).unwrap();
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn test_many_frames_roundtrip() {
+    let messages = ["first", "second #V#name#V#", "", "multi\nline\tmessage"];
+    let bytes = write_frames(&messages);
+
+    let frames: Vec<Frame> = FrameReader::new(bytes.as_slice(), &V1_STANDARD)
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(frames.len(), messages.len());
+    for (i, (frame, message)) in frames.iter().zip(messages).enumerate() {
+        assert_eq!(frame.sequence, Some(i as u64));
+        assert_eq!(frame.message, message);
+    }
+}
+
+#[test]
+fn test_plain_frames_with_custom_strategy() {
+    let mut writer = FrameWriter::with_strategy(Vec::new(), &V3_LOWERCASE, CoreStrategy::strict());
+    writer.write_message("hello world").unwrap();
+    let bytes = writer.into_inner();
+    assert!(bytes.starts_with(b"#SSX#") && bytes.ends_with(b"#ESX#\n"));
+
+    let mut reader = FrameReader::with_strategy(bytes.as_slice(), &V3_LOWERCASE, CoreStrategy::strict());
+    assert_eq!(reader.next_frame().unwrap().unwrap(), Frame { sequence: None, message: "hello world".to_string() });
+    assert!(reader.next_frame().is_none());
+}
+
+#[test]
+fn test_recovery_from_corrupted_frames() {
+    let good = write_frames(&["one", "two", "three"]);
+    let text = String::from_utf8(good).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+
+    // Truncate the first frame, corrupt the length of the second, add junk
+    let truncated = &lines[0][..lines[0].len() - 8];
+    let bad_length = lines[1].replace("len=", "len=9");
+    let stream = format!("junk{}\n{}\n???{}\n", truncated, bad_length, lines[2]);
+
+    let results: Vec<_> = FrameReader::new(stream.as_bytes(), &V1_STANDARD).collect();
+    assert_eq!(results.len(), 3);
+    assert!(matches!(results[0], Err(Asc100Error::CorruptFrame { offset: 4, .. })));
+    assert!(matches!(results[1], Err(Asc100Error::CorruptFrame { .. })));
+    assert_eq!(results[2].as_ref().unwrap().message, "three");
+    assert_eq!(results[2].as_ref().unwrap().sequence, Some(2));
+}
+
+#[test]
+fn test_unterminated_final_frame() {
+    let mut bytes = write_frames(&["ok"]);
+    bytes.extend_from_slice(b"#SSX#seq=1;QUJD");
+
+    let results: Vec<_> = FrameReader::new(bytes.as_slice(), &V1_STANDARD).collect();
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(matches!(results[1], Err(Asc100Error::CorruptFrame { .. })));
+}
@@ -0,0 +1,62 @@
+use asc100::char::versions::V1_STANDARD;
+use asc100::proto::{Dispatcher, FunctionCall, parse_calls};
+use asc100::Asc100Error;
+
+#[test]
+fn test_serialize_and_parse() {
+    let call = FunctionCall::new("deploy", &["prod", "v1.2.3"]);
+    assert_eq!(call.to_string(), "#FX#deploy#ARG#prod#ARG#v1.2.3");
+    assert_eq!(FunctionCall::parse(&call.to_string()).unwrap(), call);
+
+    // Empty arguments are preserved
+    let call = FunctionCall::new("noop", &["", "x", ""]);
+    assert_eq!(FunctionCall::parse(&call.to_string()).unwrap(), call);
+}
+
+#[test]
+fn test_quoting_and_nested_calls() {
+    let inner = FunctionCall::new("echo", &["say \"hi\"", "#ARG# is literal"]);
+    let outer = FunctionCall::new("retry", &["3", &inner.to_string()]);
+
+    let serialized = outer.to_string();
+    assert!(!serialized.contains('"'));
+
+    let parsed = FunctionCall::parse(&serialized).unwrap();
+    assert_eq!(parsed, outer);
+    assert_eq!(FunctionCall::parse(&parsed.args[1]).unwrap(), inner);
+}
+
+#[test]
+fn test_encoded_roundtrip() {
+    let call = FunctionCall::new("set", &["color", "#ff0000"]);
+    let encoded = call.encode(&V1_STANDARD).unwrap();
+    assert_eq!(FunctionCall::from_encoded(&encoded, &V1_STANDARD).unwrap(), call);
+}
+
+#[test]
+fn test_malformed_calls() {
+    assert!(matches!(parse_calls("text #FX#f"), Err(Asc100Error::MalformedCall(_))));
+    assert!(matches!(parse_calls("#ARG#x"), Err(Asc100Error::MalformedCall(_))));
+    assert!(matches!(parse_calls("#FX##ARG#x"), Err(Asc100Error::MalformedCall(_))));
+}
+
+#[test]
+fn test_dispatcher() {
+    let mut dispatcher = Dispatcher::new();
+    dispatcher
+        .register("add", |args| {
+            let sum: i64 = args.iter().map(|a| a.parse::<i64>().map_err(|e| e.to_string())).sum::<Result<_, _>>()?;
+            Ok(sum.to_string())
+        })
+        .register("upper", |args| Ok(args.join(" ").to_uppercase()));
+
+    let payload = format!("{}{}", FunctionCall::new("add", &["2", "40"]), FunctionCall::new("upper", &["a", "b"]));
+    let encoded = V1_STANDARD.encode_with(&payload, &asc100::char::extensions::ExtensionsStrategy::strict()).unwrap();
+    assert_eq!(dispatcher.dispatch_encoded(&encoded, &V1_STANDARD).unwrap(), vec!["42", "A B"]);
+
+    let err = dispatcher.dispatch(&FunctionCall::new("add", &["x"])).unwrap_err();
+    assert!(matches!(err, Asc100Error::FunctionFailed { ref name, .. } if name == "add"));
+
+    let err = dispatcher.dispatch(&FunctionCall::new("rm", &["-rf"])).unwrap_err();
+    assert!(matches!(err, Asc100Error::UnknownFunction(ref name) if name == "rm"));
+}
@@ -0,0 +1,110 @@
+use std::sync::mpsc;
+use std::thread;
+
+use asc100::char::charset::{MARKER_Q, MARKER_V, MARKER_FX};
+use asc100::char::versions::{Asc100Version, V1_STANDARD};
+use asc100::proto::{Agreement, Capabilities, Handshake, HandshakeState};
+use asc100::Asc100Error;
+
+fn caps(versions: &[&str], strategies: &[&str], markers: &[u8]) -> Capabilities {
+    Capabilities {
+        versions: versions.iter().map(|s| s.to_string()).collect(),
+        strategies: strategies.iter().map(|s| s.to_string()).collect(),
+        markers: markers.to_vec(),
+    }
+}
+
+#[test]
+fn test_handshake_over_in_memory_channel() {
+    let (to_responder, responder_inbox) = mpsc::channel::<String>();
+    let (to_initiator, initiator_inbox) = mpsc::channel::<String>();
+
+    let responder = thread::spawn(move || {
+        let mut handshake = Handshake::responder(caps(
+            &["v1_standard", "v4_url"], &["core", "extensions"], &[MARKER_V, MARKER_Q],
+        ));
+        for message in responder_inbox {
+            if let Some(reply) = handshake.receive(&message).unwrap() {
+                to_initiator.send(reply).unwrap();
+            }
+            if handshake.is_established() {
+                break;
+            }
+        }
+        handshake.agreement().cloned()
+    });
+
+    let mut initiator = Handshake::initiator(caps(
+        &["v4_url", "v1_standard"], &["extensions", "core"], &[MARKER_V, MARKER_Q, MARKER_FX],
+    ));
+    to_responder.send(initiator.start().unwrap()).unwrap();
+    let reply = initiator_inbox.recv().unwrap();
+    let ack = initiator.receive(&reply).unwrap().expect("Initiator should acknowledge");
+    to_responder.send(ack).unwrap();
+
+    let expected = Agreement {
+        version: "v4_url".to_string(),
+        strategy: "extensions".to_string(),
+        markers: vec![MARKER_V, MARKER_Q],
+    };
+    assert_eq!(initiator.agreement(), Some(&expected));
+    assert_eq!(responder.join().unwrap(), Some(expected));
+}
+
+#[test]
+fn test_default_capabilities_agree_on_v1() {
+    let mut initiator = Handshake::initiator(Capabilities::default());
+    let mut responder = Handshake::responder(Capabilities::default());
+
+    let hso = initiator.start().unwrap();
+    let hsi = responder.receive(&hso).unwrap().unwrap();
+    assert!(matches!(responder.state(), HandshakeState::AwaitingAck(_)));
+    let ack = initiator.receive(&hsi).unwrap().unwrap();
+    assert_eq!(responder.receive(&ack).unwrap(), None);
+
+    assert_eq!(responder.agreement().unwrap().version, "v1_standard");
+    assert_eq!(responder.agreement(), initiator.agreement());
+}
+
+#[test]
+fn test_no_common_version_fails() {
+    let mut initiator = Handshake::initiator(caps(&["v2_numbers_first"], &["core"], &[]));
+    let mut responder = Handshake::responder(caps(&["v1_standard"], &["core"], &[]));
+
+    let hso = initiator.start().unwrap();
+    assert!(matches!(responder.receive(&hso), Err(Asc100Error::HandshakeFailed(_))));
+}
+
+#[test]
+fn test_out_of_order_messages_are_rejected() {
+    let mut initiator = Handshake::initiator(Capabilities::default());
+    let mut other = Handshake::initiator(Capabilities::default());
+
+    let hso = other.start().unwrap();
+    // An initiator never accepts an #HSO#
+    assert!(initiator.receive(&hso).is_err());
+    // A responder cannot start
+    assert!(Handshake::responder(Capabilities::default()).start().is_err());
+}
+
+#[test]
+fn test_names_that_break_the_message_format_are_rejected() {
+    for bad in ["v5,extra", "v5#BRK#", "#HSI#", ""] {
+        let capabilities = caps(&["v1_standard", bad], &["core"], &[]);
+        assert!(matches!(capabilities.validate(), Err(Asc100Error::HandshakeFailed(_))), "{:?}", bad);
+        assert!(Handshake::initiator(capabilities.clone()).start().is_err());
+
+        let hso = Handshake::initiator(Capabilities::default()).start().unwrap();
+        assert!(Handshake::responder(capabilities).receive(&hso).is_err());
+    }
+    assert!(caps(&["v1_standard"], &["core", "extensions+sanitize"], &[]).validate().is_ok());
+
+    // A runtime custom name travels intact
+    let name = Asc100Version::custom_owned(format!("tenant_{}", 7), V1_STANDARD.charset).unwrap().name;
+    let mut initiator = Handshake::initiator(caps(&[name, "v1_standard"], &["core"], &[]));
+    let mut responder = Handshake::responder(caps(&["v1_standard", name], &["core"], &[]));
+    let hsi = responder.receive(&initiator.start().unwrap()).unwrap().unwrap();
+    let ack = initiator.receive(&hsi).unwrap().unwrap();
+    responder.receive(&ack).unwrap();
+    assert_eq!(responder.agreement().unwrap().version, "tenant_7");
+}
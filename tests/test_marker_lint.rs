@@ -0,0 +1,53 @@
+use asc100::char::versions::V1_STANDARD;
+use asc100::char::extensions::{CoreStrategy, ExtensionsStrategy};
+use asc100::proto::{tokenize, validate_markers, IssueKind, LintedStrategy, Severity};
+use asc100::Asc100Error;
+
+#[test]
+fn test_well_formed_payload_is_clean() {
+    let input = "#SSX# #MEM# version=1.0 #CTX# User: #V#username#V# #FX#process#ARG#x#ESX##EOF#";
+    let issues = validate_markers(&tokenize(input));
+    assert!(issues.is_empty(), "Unexpected issues: {:?}", issues);
+}
+
+#[test]
+fn test_structural_issues_are_reported_with_positions() {
+    let input = "#ESX#a #ARG#b #V#open #SSX# c";
+    let issues = validate_markers(&tokenize(input));
+
+    let kinds: Vec<_> = issues.iter().map(|issue| (issue.kind.clone(), issue.position)).collect();
+    assert_eq!(kinds, vec![
+        (IssueKind::UnopenedStream, 0),
+        (IssueKind::OrphanArgument, 7),
+        (IssueKind::UnterminatedVariable, 14),
+        (IssueKind::UnclosedStream, 22),
+    ]);
+    assert!(issues.iter().all(|issue| issue.severity == Severity::Error));
+}
+
+#[test]
+fn test_warnings_for_eof_trailer_and_lookalikes() {
+    let issues = validate_markers(&tokenize("body #VAR# #EOF#junk"));
+
+    assert_eq!(issues.len(), 2);
+    assert_eq!(issues[0].kind, IssueKind::UnknownMarker("#VAR#".to_string()));
+    assert_eq!(issues[0].position, 5);
+    assert_eq!(issues[1].kind, IssueKind::ContentAfterEof);
+    assert_eq!(issues[1].position, 16);
+    assert!(issues.iter().all(|issue| issue.severity == Severity::Warning));
+}
+
+#[test]
+fn test_linted_strategy_rejects_errors_only() {
+    let strategy = LintedStrategy::new(ExtensionsStrategy::strict());
+
+    let encoded = V1_STANDARD.encode_with("#V#name#V# #NOPE#", &strategy).expect("Warnings should not block");
+    assert_eq!(V1_STANDARD.decode_with(&encoded, &strategy).unwrap(), "#V#name#V# #NOPE#");
+
+    let err = V1_STANDARD.encode_with("#SSX# never closed", &strategy).unwrap_err();
+    assert!(matches!(err, Asc100Error::MalformedMarkers(ref issue) if issue.kind == IssueKind::UnclosedStream));
+
+    // Core strategy treats markers as text, so there is nothing to lint
+    let core = LintedStrategy::new(CoreStrategy::strict());
+    assert!(V1_STANDARD.encode_with("#SSX# never closed", &core).is_ok());
+}
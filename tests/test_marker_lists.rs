@@ -0,0 +1,65 @@
+use asc100::char::versions::V1_STANDARD;
+use asc100::char::extensions::ExtensionsStrategy;
+use asc100::char::charset::{MARKER_V, MARKER_Q, MARKER_E, MARKER_HSO, MARKER_TR, MARKER_FX, MARKER_INV};
+use asc100::Asc100Error;
+
+#[test]
+fn test_allow_list_roundtrip() {
+    let strategy = ExtensionsStrategy::strict().allow_markers(&[MARKER_V, MARKER_Q, MARKER_E]);
+    let input = "Hello #V#name#V#, say #Q#hi#Q#";
+
+    let encoded = V1_STANDARD.encode_with(input, &strategy).expect("Allowed markers should encode");
+    let decoded = V1_STANDARD.decode_with(&encoded, &strategy).expect("Allowed markers should decode");
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_allow_list_rejects_on_encode() {
+    let strategy = ExtensionsStrategy::strict().allow_markers(&[MARKER_V, MARKER_Q, MARKER_E]);
+
+    match V1_STANDARD.encode_with("user #HSO# data", &strategy) {
+        Err(Asc100Error::RejectedMarker { marker, position }) => {
+            assert_eq!(marker, MARKER_HSO);
+            assert_eq!(position, 5);
+        }
+        other => panic!("Expected rejected marker, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_encode_position_refers_to_original_input() {
+    // "café " is 6 bytes; Sanitize grows it to "caf#INV# " and Strip shrinks it to "caf "
+    let input = "café #HSO# data";
+    for result in [
+        V1_STANDARD.encode_with(input, &ExtensionsStrategy::sanitize().allow_markers(&[MARKER_INV])),
+        V1_STANDARD.encode_with(input, &ExtensionsStrategy::strip().allow_markers(&[])),
+    ] {
+        assert!(matches!(result, Err(Asc100Error::RejectedMarker { marker: MARKER_HSO, position: 6 })), "{:?}", result);
+    }
+
+    // A marker introduced by the filter points at the character it replaced
+    let result = V1_STANDARD.encode_with(input, &ExtensionsStrategy::sanitize().allow_markers(&[MARKER_HSO]));
+    assert!(matches!(result, Err(Asc100Error::RejectedMarker { marker: MARKER_INV, position: 3 })), "{:?}", result);
+}
+
+#[test]
+fn test_deny_list_rejects_on_decode() {
+    // Payload produced by an unrestricted sender
+    let open = ExtensionsStrategy::strict();
+    let encoded = V1_STANDARD.encode_with("ok #V#x#V# then #FX#run", &open).unwrap();
+
+    let strategy = ExtensionsStrategy::strict().deny_markers(&[MARKER_HSO, MARKER_TR, MARKER_FX]);
+    let err = V1_STANDARD.decode_with(&encoded, &strategy).unwrap_err();
+
+    assert!(matches!(err, Asc100Error::RejectedMarker { marker: MARKER_FX, position: 16 }));
+    assert_eq!(err.to_string(), "Marker #FX# not allowed at position 16");
+}
+
+#[test]
+fn test_unknown_marker_lookalikes_stay_text() {
+    let strategy = ExtensionsStrategy::strict().allow_markers(&[MARKER_V]);
+    let input = "#NOPE# is plain text";
+
+    let encoded = V1_STANDARD.encode_with(input, &strategy).unwrap();
+    assert_eq!(V1_STANDARD.decode_with(&encoded, &strategy).unwrap(), input);
+}
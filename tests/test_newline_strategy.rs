@@ -0,0 +1,48 @@
+use asc100::char::extensions::{ExtensionsStrategy, LineEnding};
+use asc100::char::versions::{V1_STANDARD, V2_NUMBERS};
+use asc100::Asc100Error;
+
+#[test]
+fn test_line_endings_encode_identically() {
+    let strategy = ExtensionsStrategy::strict().normalize_newlines(&V1_STANDARD, LineEnding::Lf);
+    let lf = V1_STANDARD.encode_with("one\ntwo\nthree\n", &strategy).unwrap();
+    let crlf = V1_STANDARD.encode_with("one\r\ntwo\r\nthree\r\n", &strategy).unwrap();
+    let cr = V1_STANDARD.encode_with("one\rtwo\rthree\r", &strategy).unwrap();
+    assert_eq!(lf, crlf);
+    assert_eq!(lf, cr);
+
+    // Same payload as writing the markers explicitly
+    let explicit = V1_STANDARD.encode_with("one#NL#two#NL#three#NL#", &ExtensionsStrategy::strict()).unwrap();
+    assert_eq!(lf, explicit);
+}
+
+#[test]
+fn test_decode_rebuilds_chosen_ending() {
+    let input = "a\r\nb\nc\rd";
+    let cases = [(LineEnding::Lf, "a\nb\nc\nd"), (LineEnding::CrLf, "a\r\nb\r\nc\r\nd"), (LineEnding::Cr, "a\rb\rc\rd")];
+    for (ending, expected) in cases {
+        let strategy = ExtensionsStrategy::strict().normalize_newlines(&V2_NUMBERS, ending);
+        let encoded = V2_NUMBERS.encode_with(input, &strategy).unwrap();
+        assert_eq!(V2_NUMBERS.decode_with(&encoded, &strategy).unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_original_ending_is_recorded() {
+    let strategy = ExtensionsStrategy::strict().normalize_newlines(&V1_STANDARD, LineEnding::Original);
+    for input in ["x\r\ny\r\n", "x\ry", "\nx\ny", "no newline", "", "\r\n\r\n"] {
+        let encoded = V1_STANDARD.encode_with(input, &strategy).unwrap();
+        assert_eq!(V1_STANDARD.decode_with(&encoded, &strategy).unwrap(), input);
+    }
+
+    // Mixed input comes back with its first ending throughout
+    let encoded = V1_STANDARD.encode_with("a\r\nb\nc", &strategy).unwrap();
+    assert_eq!(V1_STANDARD.decode_with(&encoded, &strategy).unwrap(), "a\r\nb\r\nc");
+}
+
+#[test]
+fn test_original_requires_flag() {
+    let plain = V1_STANDARD.encode_with("a\nb", &ExtensionsStrategy::strict()).unwrap();
+    let strategy = ExtensionsStrategy::strict().normalize_newlines(&V1_STANDARD, LineEnding::Original);
+    assert!(matches!(V1_STANDARD.decode_with(&plain, &strategy), Err(Asc100Error::MissingNewlineFlag)));
+}
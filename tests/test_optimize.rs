@@ -0,0 +1,90 @@
+use asc100::char::charset::{MARKER_EOF, MARKER_V};
+use asc100::char::extensions::ExtensionsStrategy;
+use asc100::char::versions::{Asc100Version, Packing, V1_STANDARD, V2_NUMBERS};
+use asc100::optimize::{to_rust_source, CorpusStats};
+
+const CORPUS: &[&str] = &[
+    "the quick brown fox jumps over the lazy dog",
+    "it was the best of times, it was the worst of times",
+    "#V#name#V# said hello to the world#EOF#",
+];
+
+#[test]
+fn test_counts_chars_and_markers() {
+    let stats = CorpusStats::from_corpus(CORPUS.iter().copied());
+    assert_eq!(stats.char_count('q'), 1);
+    assert_eq!(stats.char_count('#'), 0);
+    assert_eq!(stats.marker_count(MARKER_V), 2);
+    assert_eq!(stats.marker_count(MARKER_EOF), 1);
+    assert_eq!(stats.unsupported(), 0);
+
+    let mut stats = CorpusStats::new();
+    stats.add("a#b#c").add("café");
+    assert_eq!(stats.char_count('#'), 2);
+    assert_eq!(stats.unsupported(), 1);
+    assert_eq!(stats.total(), 8);
+}
+
+#[test]
+fn test_optimal_version_orders_by_frequency() {
+    let stats = CorpusStats::from_corpus(CORPUS.iter().copied());
+    let version = stats.optimal_version("v5_prose");
+    assert_eq!(version.packing, Packing::Variable);
+    assert_eq!(version.charset[0], ' ');
+    for pair in version.charset.windows(2) {
+        assert!(stats.char_count(pair[0]) >= stats.char_count(pair[1]));
+    }
+    // Still a permutation of the base charset
+    assert!(Asc100Version::custom("check", version.charset).is_ok());
+
+    let strategy = ExtensionsStrategy::strict();
+    for &sample in CORPUS {
+        let encoded = version.encode_with(sample, &strategy).unwrap();
+        assert_eq!(version.decode_with(&encoded, &strategy).unwrap(), sample);
+    }
+}
+
+#[test]
+fn test_expected_bits_match_encoded_size() {
+    let stats = CorpusStats::from_corpus([CORPUS[0]]);
+    let version = stats.optimal_version("v5_fox");
+    let encoded = version.encode(CORPUS[0]).unwrap();
+    let bits = stats.expected_bits(&version);
+    // Tag plus padding to a whole character
+    assert_eq!(encoded.len() as u64, 1 + bits.saturating_add(5) / 6);
+    assert_eq!(stats.expected_bits(&V1_STANDARD), 7 * CORPUS[0].len() as u64);
+}
+
+#[test]
+fn test_report_compares_builtins() {
+    let stats = CorpusStats::from_corpus(CORPUS.iter().copied());
+    let version = stats.optimal_version("v5_prose");
+    let report = stats.report(&version);
+    let names: Vec<&str> = report.rows.iter().map(|row| row.name.as_str()).collect();
+    assert_eq!(names, ["fixed", "v1_standard", "v2_numbers_first", "v3_lowercase_first", "v4_url_optimized", "v5_prose"]);
+    assert_eq!(report.best().unwrap().name, "v5_prose");
+    assert!(report.rows.iter().all(|row| row.bits >= report.rows[5].bits));
+
+    let text = report.to_string();
+    assert!(text.contains("v5_prose"));
+    assert!(text.contains("vs fixed"));
+
+    let digits = CorpusStats::from_corpus(["3.14159 2.71828 1.41421"]);
+    let report = digits.report(&digits.optimal_version("v5_digits"));
+    assert!(report.rows[5].bits <= digits.expected_bits(&V2_NUMBERS.variable()));
+}
+
+#[test]
+fn test_rust_source_output() {
+    let stats = CorpusStats::from_corpus(["it's a \"test\"\n\t\\"]);
+    let version = stats.optimal_version("v5_quotes");
+    let source = to_rust_source(&version, "V5_QUOTES");
+    assert!(source.contains("pub const V5_QUOTES: Asc100Version = Asc100Version {"));
+    assert!(source.contains("name: \"v5_quotes\","));
+    assert!(source.contains("lookup: build_lookup_table(V5_QUOTES_CHARSET),"));
+    assert!(source.contains("packing: Packing::Variable,"));
+    assert!(source.contains("'\\''") && source.contains("'\\\\'") && source.contains("'\\n'"));
+    // Every charset entry is emitted as a char literal
+    assert!(version.charset.iter().all(|ch| source.contains(&format!("{:?},", ch))));
+    assert_eq!(source.lines().filter(|line| line.starts_with("    '")).count(), 10);
+}
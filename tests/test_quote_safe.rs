@@ -0,0 +1,88 @@
+use asc100::char::charset::{create_base_charset, marker_name, MARKER_BSL, MARKER_E};
+use asc100::char::extensions::{
+    parse_quote_safe, render_quote_safe, ExtensionsStrategy, QuoteSafeStrategy, StrictFilter,
+};
+use asc100::char::versions::{V1_STANDARD, V3_LOWERCASE};
+use asc100::proto::{Envelope, FunctionCall};
+
+const QUOTED: &str = r#"say "hi", it's C:\tmp\x"#;
+
+#[test]
+fn test_quote_safe_roundtrip_all_versions() {
+    for version in [V1_STANDARD, V3_LOWERCASE] {
+        let strategy = ExtensionsStrategy::strict().quote_safe(&version);
+        let encoded = version.encode_with(QUOTED, &strategy).unwrap();
+        assert_eq!(version.decode_with(&encoded, &strategy).unwrap(), QUOTED);
+    }
+}
+
+#[test]
+fn test_quotes_travel_as_markers() {
+    let strategy = QuoteSafeStrategy::new(StrictFilter, &V1_STANDARD);
+    let encoded = V1_STANDARD.encode_with(r#""a'\"#, &strategy).unwrap();
+
+    // Decoding without quote restoration shows the registered marker names
+    let raw = V1_STANDARD.decode_with(&encoded, &ExtensionsStrategy::strict()).unwrap();
+    assert_eq!(raw, "#Q#a#E##BSL#");
+    assert_eq!(marker_name(MARKER_E), Some("#E#"));
+    assert_eq!(marker_name(MARKER_BSL), Some("#BSL#"));
+}
+
+#[test]
+fn test_marker_text_is_unaffected() {
+    let strategy = ExtensionsStrategy::strict().quote_safe(&V1_STANDARD);
+    let input = r#"a#b"c' #E# #Q# #BSL#"#;
+    let encoded = V1_STANDARD.encode_with(input, &strategy).unwrap();
+    assert_eq!(V1_STANDARD.decode_with(&encoded, &strategy).unwrap(), input);
+}
+
+#[test]
+fn test_quote_safe_rendering() {
+    let text = "it's \"x\"\\\nnext\tcol\r #E# #";
+    let rendered = render_quote_safe(text);
+    assert_eq!(rendered, "it#E#s #Q#x#Q##BSL##NL#next#CHR#09col#CHR#0D #CHR#23E# #");
+    assert_eq!(parse_quote_safe(&rendered), text);
+
+    let encoded = V1_STANDARD.encode_with(QUOTED, &ExtensionsStrategy::strict()).unwrap();
+    let pasted = V1_STANDARD.decode_quote_safe(&encoded).unwrap();
+    assert_eq!(pasted, "say #Q#hi#Q#, it#E#s C:#BSL#tmp#BSL#x");
+
+    // Payloads encoded quote-safe render the same way
+    let strategy = ExtensionsStrategy::strict().quote_safe(&V1_STANDARD);
+    let encoded = V1_STANDARD.encode_with(QUOTED, &strategy).unwrap();
+    assert_eq!(V1_STANDARD.decode_quote_safe(&encoded).unwrap(), pasted);
+    assert_eq!(parse_quote_safe(&pasted), QUOTED);
+}
+
+#[test]
+fn test_rendered_charset_is_a_json_string() {
+    let text: String = create_base_charset().iter().collect();
+    let rendered = render_quote_safe(&text);
+    assert!(!rendered.contains(['"', '\'', '\\']));
+    assert!(!rendered.chars().any(|ch| ch.is_ascii_control()));
+
+    let json = format!("\"{}\"", rendered);
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.as_str(), Some(rendered.as_str()));
+    assert_eq!(parse_quote_safe(&rendered), text);
+}
+
+#[test]
+fn test_proto_escapes_through_quote_safe() {
+    // Protocol text escapes `#` as #E#, which quote-safe encoding keeps apart from `'`
+    let call = FunctionCall::new("echo", &["it's #1", "a#E#b", r"C:\x"]);
+    let envelope = Envelope::new("body's #tag")
+        .with_meta("note", "'#'")
+        .unwrap();
+    let strategy = ExtensionsStrategy::strict().quote_safe(&V1_STANDARD);
+
+    let encoded = V1_STANDARD.encode_with(&call.to_string(), &strategy).unwrap();
+    assert_eq!(FunctionCall::parse(&V1_STANDARD.decode_with(&encoded, &strategy).unwrap()).unwrap(), call);
+
+    let encoded = V1_STANDARD.encode_with(&envelope.to_string(), &strategy).unwrap();
+    assert_eq!(Envelope::parse(&V1_STANDARD.decode_with(&encoded, &strategy).unwrap()).unwrap(), envelope);
+
+    // Rendering the decoded envelope for pasting and parsing it back is lossless too
+    let pasted = V1_STANDARD.decode_quote_safe(&encoded).unwrap();
+    assert_eq!(Envelope::parse(&parse_quote_safe(&pasted)).unwrap(), envelope);
+}
@@ -0,0 +1,49 @@
+use asc100::char::versions::V1_STANDARD;
+use asc100::proto::{decode_nested, decode_records, decode_records_with, encode_nested, encode_records, encode_records_with, Record};
+use asc100::Asc100Error;
+
+#[test]
+fn test_records_roundtrip_with_delimiters_in_content() {
+    let records = ["name,age", "a#BRK#b", "say \"hi\"", "/usr/bin:/bin", "#"];
+    let text = encode_records(&records);
+    assert_eq!(decode_records(&text), records);
+
+    let encoded = encode_records_with(&records, &V1_STANDARD).unwrap();
+    assert_eq!(decode_records_with(&encoded, &V1_STANDARD).unwrap(), records);
+}
+
+#[test]
+fn test_empty_records_are_kept() {
+    let records = ["", "a", "", ""];
+    let text = encode_records(&records);
+    assert_eq!(text, "#BRK#a#BRK##BRK#");
+    assert_eq!(decode_records(&text), records);
+    assert_eq!(decode_records(""), vec![String::new()]);
+}
+
+#[test]
+fn test_nested_lists() {
+    let records = vec![
+        Record::item("argv"),
+        Record::list(&["ls", "-la", "my dir"]),
+        Record::List(vec![Record::item("x"), Record::list(&[]), Record::list(&["", "y"])]),
+        Record::item(""),
+    ];
+    let text = encode_nested(&records);
+    assert_eq!(decode_nested(&text).unwrap(), records);
+
+    // The flat view returns nested lists as record text for another pass
+    let flat = decode_records(&text);
+    assert_eq!(flat.len(), 4);
+    assert_eq!(decode_records(&flat[1]), vec!["ls", "-la", "my dir"]);
+}
+
+#[test]
+fn test_malformed_nesting() {
+    for text in ["a#BRK##SSX#b", "a#ESX#", "a#SSX#b#ESX#", "#SSX#b#ESX#c"] {
+        assert!(
+            matches!(decode_nested(text), Err(Asc100Error::MalformedRecords(_))),
+            "expected error for {}", text
+        );
+    }
+}
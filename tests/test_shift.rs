@@ -0,0 +1,143 @@
+use asc100::char::charset::create_base_charset;
+use asc100::char::extensions::{EncodingStrategy, ExtensionsStrategy};
+use asc100::char::versions::{Asc100Version, Packing, V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL};
+use asc100::codec::Codec;
+use asc100::shift::{in_lower_plane, is_shift, LOWER_PLANE, SHIFT_TAG, UPPER_CHARS};
+use asc100::transcode::transcode;
+use asc100::Asc100Error;
+
+const SAMPLES: &[&str] = &[
+    "",
+    "a",
+    "A",
+    "Hello, World! 123",
+    "the quick brown fox jumps over the lazy dog",
+    "SHOUTING IN ALL CAPS then quiet",
+    "Mixed Case Words And $ymbols {~|} \r\0\x01",
+    "https://example.com/path?query=value&x=1",
+];
+
+fn every_index() -> Vec<u8> {
+    (0..128).chain((0..128).rev()).chain([124, 125, 126, 127, 62, 126, 124]).collect()
+}
+
+/// Strategy that replaces the stream with every index, including markers
+/// 124-127, and on decode checks it came back unchanged
+struct AllIndices;
+
+impl EncodingStrategy for AllIndices {
+    fn preprocess(&self, input: &str) -> Result<String, Asc100Error> {
+        Ok(input.to_string())
+    }
+
+    fn postprocess(&self, output: &str) -> String {
+        output.to_string()
+    }
+
+    fn supports_index(&self, _index: u8) -> bool {
+        true
+    }
+
+    fn encode_indices(&self, _indices: Vec<u8>) -> Result<Vec<u8>, Asc100Error> {
+        Ok(every_index())
+    }
+
+    fn decode_indices(&self, indices: Vec<u8>) -> Result<Vec<u8>, Asc100Error> {
+        if indices != every_index() {
+            return Err(Asc100Error::MalformedRecords(format!("{:?}", indices)));
+        }
+        Ok(Vec::new())
+    }
+}
+
+#[test]
+fn test_planes_cover_charset() {
+    assert_eq!(LOWER_PLANE.chars().count(), 62);
+    assert_eq!(UPPER_CHARS.chars().count(), 38);
+    for ch in create_base_charset() {
+        assert!(LOWER_PLANE.contains(ch) != UPPER_CHARS.contains(ch), "{:?}", ch);
+    }
+    assert!(in_lower_plane('e') && in_lower_plane(' ') && !in_lower_plane('E'));
+}
+
+#[test]
+fn test_shift_roundtrip_all_versions() {
+    for version in [V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL] {
+        let shifted = version.shifted();
+        assert_eq!(shifted.packing, Packing::Shift);
+        for &input in SAMPLES {
+            let encoded = shifted.encode(input).unwrap();
+            assert!(is_shift(&encoded) && encoded.starts_with(SHIFT_TAG), "{:?}", encoded);
+            assert_eq!(shifted.decode(&encoded).unwrap(), input, "{} {:?}", version.name, input);
+            assert_eq!(version.decode(&encoded).unwrap(), input);
+        }
+    }
+
+    let strategy = ExtensionsStrategy::strict();
+    let input = "#SSX#Name=#V#value#V##ESX##EOF#";
+    let encoded = V3_LOWERCASE.shifted().encode_with(input, &strategy).unwrap();
+    assert_eq!(V1_STANDARD.decode_with(&encoded, &strategy).unwrap(), input);
+}
+
+#[test]
+fn test_every_index_roundtrips() {
+    for version in [V1_STANDARD, V4_URL] {
+        let encoded = version.shifted().encode_with("x", &AllIndices).unwrap();
+        assert_eq!(version.decode_with(&encoded, &AllIndices).unwrap(), "");
+        let codec = Codec::builder().version(version).strategy(AllIndices).canonical(true).build().unwrap();
+        assert_eq!(codec.decode(&encoded).unwrap(), "");
+    }
+}
+
+#[test]
+fn test_lowercase_text_shrinks() {
+    let prose = "it was the best of times, it was the worst of times, it was the age of wisdom";
+    let fixed = V1_STANDARD.encode(prose).unwrap();
+    let shifted = V1_STANDARD.shifted().encode(prose).unwrap();
+    // One character per symbol plus the tag, against 7/6 characters per symbol:
+    // 6 bits instead of 7 is about 14% smaller, not the 25% of 6 against 8
+    assert_eq!(shifted.len(), prose.len() + 1);
+    let ratio = shifted.len() as f64 / fixed.len() as f64;
+    assert!((ratio - 6.0 / 7.0).abs() < 0.02, "{}", ratio);
+
+    // Capitalized words shift, runs of capitals latch
+    let shout = V1_STANDARD.shifted().encode("Hello World").unwrap();
+    assert_eq!(shout.len(), 1 + 11 + 2);
+    let caps = V1_STANDARD.shifted().encode("ABCDEFGH").unwrap();
+    assert_eq!(caps.len(), 1 + 1 + 8);
+}
+
+#[test]
+fn test_canonical_and_identical_across_versions() {
+    let input = "Same payload in every version";
+    let encoded = V2_NUMBERS.shifted().encode(input).unwrap();
+    assert_eq!(V4_URL.shifted().encode(input).unwrap(), encoded);
+    assert_eq!(transcode(&encoded, &V2_NUMBERS, &V3_LOWERCASE).unwrap(), encoded);
+
+    let codec = Codec::builder().version(Asc100Version::custom("custom", V3_LOWERCASE.charset).unwrap().shifted())
+        .canonical(true)
+        .build()
+        .unwrap();
+    assert_eq!(codec.decode(&encoded).unwrap(), input);
+    // Trailing shift ("+" is code 62) or latch ("/" is code 63) carries no symbol
+    assert_eq!(V1_STANDARD.decode(&format!("{}+", encoded)).unwrap(), input);
+    assert!(codec.decode(&format!("{}+", encoded)).is_err());
+    assert!(codec.decode(&format!("{}/", encoded)).is_err());
+    // Shift followed by 62 is a complete symbol (marker 124)
+    assert!(codec.decode(&format!("{}++", encoded)).is_err_and(|e| !e.to_string().contains("canonical")));
+}
+
+#[test]
+fn test_canonical_rejects_redundant_plane_codes() {
+    let codec = Codec::builder().version(V1_STANDARD.shifted()).canonical(true).build().unwrap();
+    assert_eq!(V1_STANDARD.shifted().encode("ab").unwrap(), "^AB");
+    assert_eq!(V1_STANDARD.shifted().encode("aB").unwrap(), "^A+B");
+    assert_eq!(codec.decode("^A+B").unwrap(), "aB");
+
+    // "/" is a latch and "+" a shift: each payload decodes, but no encoder writes it
+    for (payload, text) in [("^//AB", "ab"), ("^A//B", "ab"), ("^A/+B/", "ab"), ("^/+AB", "aB")] {
+        assert_eq!(V1_STANDARD.decode(payload).unwrap(), text, "{}", payload);
+        let err = codec.decode(payload).unwrap_err();
+        assert!(matches!(err, Asc100Error::NonCanonicalPayload(_)), "{} {:?}", payload, err);
+    }
+}
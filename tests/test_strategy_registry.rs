@@ -0,0 +1,80 @@
+use asc100::char::extensions::{
+    CoreStrategy, DynStrategy, EncodingStrategy, ExtensionsStrategy, FilterAction, FilterStrategy, VersionedStrategy,
+};
+use asc100::char::registry::{strategy_by_name, StrategyRegistry};
+use asc100::char::versions::V1_STANDARD;
+use asc100::Asc100Error;
+
+#[test]
+fn test_builtin_names_match_generic_strategies() {
+    let input = "Hello #V# café";
+    let cases: [(&str, Box<dyn EncodingStrategy>); 3] = [
+        ("extensions+sanitize", Box::new(ExtensionsStrategy::sanitize())),
+        ("core+strip", Box::new(CoreStrategy::strip())),
+        ("extensions+strip", Box::new(ExtensionsStrategy::strip())),
+    ];
+    for (name, generic) in cases {
+        let dynamic = strategy_by_name(name).unwrap();
+        assert_eq!(
+            V1_STANDARD.encode_with(input, &dynamic).unwrap(),
+            V1_STANDARD.encode_with(input, &generic).unwrap(),
+            "{}", name
+        );
+    }
+
+    // The filter defaults to strict
+    let strict = strategy_by_name("core").unwrap();
+    assert!(V1_STANDARD.encode_with(input, &strict).is_err());
+}
+
+#[test]
+fn test_dyn_strategies_are_send_sync_and_wrappable() {
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    let strategy: DynStrategy = strategy_by_name("extensions").unwrap();
+    assert_send_sync(&strategy);
+
+    let versioned = VersionedStrategy::new(strategy, &V1_STANDARD);
+    let encoded = V1_STANDARD.encode_with("stamped", &versioned).unwrap();
+    assert_eq!(V1_STANDARD.decode_with(&encoded, &versioned).unwrap(), "stamped");
+
+    // A trait object can be used directly as well
+    let by_ref: &dyn EncodingStrategy = &ExtensionsStrategy::strict();
+    assert_eq!(V1_STANDARD.decode_with(&encoded, by_ref).unwrap(), "#X#stamped");
+}
+
+struct UppercaseFilter;
+
+impl FilterStrategy for UppercaseFilter {
+    fn handle_char(&self, ch: char) -> FilterAction {
+        if ch.is_ascii_lowercase() {
+            FilterAction::Replace(ch.to_ascii_uppercase().to_string())
+        } else {
+            FilterAction::Keep
+        }
+    }
+}
+
+#[test]
+fn test_third_party_registration() {
+    let mut registry = StrategyRegistry::new();
+    registry
+        .register_filter("upper", || Box::new(UppercaseFilter))
+        .register_strategy("stamped", |filter| {
+            Box::new(VersionedStrategy::new(ExtensionsStrategy { filter }, &V1_STANDARD))
+        });
+
+    assert!(registry.strategy_names().contains(&"stamped"));
+    assert_eq!(registry.filter_names(), vec!["sanitize", "strict", "strip", "upper"]);
+
+    let strategy = registry.build("stamped+upper").unwrap();
+    let encoded = V1_STANDARD.encode_with("shout", &strategy).unwrap();
+    assert_eq!(V1_STANDARD.decode_with(&encoded, &strategy).unwrap(), "SHOUT");
+}
+
+#[test]
+fn test_unknown_names() {
+    for name in ["missing", "core+missing", "+strict", ""] {
+        assert!(matches!(strategy_by_name(name), Err(Asc100Error::UnknownStrategy(_))), "{}", name);
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use asc100::char::versions::V1_STANDARD;
+use asc100::char::extensions::ExtensionsStrategy;
+use asc100::proto::{Template, TemplatePart, render_encoded};
+use asc100::Asc100Error;
+
+#[test]
+fn test_parse_and_render() {
+    let template = Template::parse("Hello #V#name#V#, welcome to #V#place#V#!").unwrap();
+    assert_eq!(template.variables(), vec!["name", "place"]);
+
+    let values = HashMap::from([("name", "Ada"), ("place", "ASC100")]);
+    assert_eq!(template.render(&values).unwrap(), "Hello Ada, welcome to ASC100!");
+}
+
+#[test]
+fn test_defaults_and_required() {
+    let template = Template::parse("#V#host=localhost#V#:#V#port#V# (#V#host#V#)").unwrap();
+
+    assert_eq!(template.variables(), vec!["host", "port"]);
+    assert_eq!(template.required(), vec!["port"]);
+    assert_eq!(template.default_for("host"), Some("localhost"));
+
+    let values = HashMap::from([("port", "8080")]);
+    assert_eq!(template.render(&values).unwrap(), "localhost:8080 (localhost)");
+
+    match template.render(&HashMap::new()) {
+        Err(Asc100Error::MissingVariable(name)) => assert_eq!(name, "port"),
+        other => panic!("Expected missing variable, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_other_markers_stay_literal() {
+    let template = Template::parse("#SSX#value=#V#v#V##ESX#").unwrap();
+    assert_eq!(template.parts()[0], TemplatePart::Literal("#SSX#value=".to_string()));
+
+    let values = HashMap::from([("v", "42")]);
+    assert_eq!(template.render(&values).unwrap(), "#SSX#value=42#ESX#");
+}
+
+#[test]
+fn test_unterminated_variable_is_rejected() {
+    assert!(matches!(Template::parse("Hi #V#name"), Err(Asc100Error::MalformedMarkers(_))));
+}
+
+#[test]
+fn test_render_from_encoded_payload() {
+    let encoded = V1_STANDARD
+        .encode_with("user=#V#user#V#; mode=#V#mode=debug#V#", &ExtensionsStrategy::strict())
+        .unwrap();
+
+    let values = HashMap::from([("user", "john")]);
+    assert_eq!(render_encoded(&encoded, &V1_STANDARD, &values).unwrap(), "user=john; mode=debug");
+}
@@ -0,0 +1,82 @@
+use std::io::Write;
+
+use asc100::char::charset::{MARKER_X, MARKER_X_V4};
+use asc100::char::extensions::{ExtensionsStrategy, VersionedStrategy};
+use asc100::char::versions::{V1_STANDARD, V2_NUMBERS, V4_URL};
+use asc100::transcode::{transcode, Permutation, TranscodeWriter};
+
+const SAMPLES: &[&str] = &[
+    "",
+    "a",
+    "Hello, World! 123",
+    "https://example.com/path?query=value&x=1",
+    "\tTabs\nand\r\nnewlines~",
+];
+
+#[test]
+fn test_transcode_matches_reencoding() {
+    for &input in SAMPLES {
+        let v1 = V1_STANDARD.encode(input).unwrap();
+        let v4 = transcode(&v1, &V1_STANDARD, &V4_URL).unwrap();
+        assert_eq!(v4, V4_URL.encode(input).unwrap(), "{:?}", input);
+        assert_eq!(transcode(&v4, &V4_URL, &V1_STANDARD).unwrap(), v1);
+    }
+}
+
+#[test]
+fn test_markers_are_preserved() {
+    let strategy = ExtensionsStrategy::strict();
+    let input = "#SSX#value=#V#name#V##ESX#";
+    let v2 = transcode(&V1_STANDARD.encode_with(input, &strategy).unwrap(), &V1_STANDARD, &V2_NUMBERS).unwrap();
+    assert_eq!(V2_NUMBERS.decode_with(&v2, &strategy).unwrap(), input);
+
+    // Markers map to themselves, except the control markers, which trade places
+    let permutation = Permutation::new(&V1_STANDARD, &V4_URL).unwrap();
+    assert!((100..128).filter(|&index| index != MARKER_X && index != MARKER_X_V4).all(|index| permutation.apply(index) == index));
+    assert_eq!(permutation.apply(MARKER_X), MARKER_X_V4);
+    assert_eq!(permutation.apply(MARKER_X_V4), MARKER_X);
+    assert!(Permutation::new(&V4_URL, &V4_URL).unwrap().is_identity());
+}
+
+#[test]
+fn test_stamped_payloads_stay_valid() {
+    let input = "stamped #V#name#V#";
+    let v1_strategy = VersionedStrategy::new(ExtensionsStrategy::strict(), &V1_STANDARD);
+    let v4_strategy = VersionedStrategy::new(ExtensionsStrategy::strict(), &V4_URL).suffix();
+    for from in [V1_STANDARD, V1_STANDARD.variable(), V1_STANDARD.shifted()] {
+        let stamped = from.encode_with(input, &v1_strategy).unwrap();
+        let v4 = transcode(&stamped, &V1_STANDARD, &V4_URL).unwrap();
+        // A prefix stamp is still a stamp; only the marker changes
+        let v4_prefix = VersionedStrategy::new(ExtensionsStrategy::strict(), &V4_URL);
+        assert_eq!(V4_URL.decode_with(&v4, &v4_prefix).unwrap(), input, "{:?}", from.packing);
+        assert!(V4_URL.decode_with(&v4, &v1_strategy).is_err());
+        assert_eq!(transcode(&v4, &V4_URL, &V1_STANDARD).unwrap(), stamped);
+    }
+
+    let stamped = V4_URL.encode_with(input, &v4_strategy).unwrap();
+    let v1 = transcode(&stamped, &V4_URL, &V1_STANDARD).unwrap();
+    assert_eq!(V1_STANDARD.decode_with(&v1, &v1_strategy.suffix()).unwrap(), input);
+}
+
+#[test]
+fn test_streaming_writer_matches_batch() {
+    let lines: Vec<String> = SAMPLES.iter().map(|input| V1_STANDARD.encode(input).unwrap()).collect();
+    let input = lines.join("\n") + "\n";
+
+    let mut writer = TranscodeWriter::new(Vec::new(), &V1_STANDARD, &V4_URL).unwrap();
+    // Uneven writes split payloads across calls
+    for piece in input.as_bytes().chunks(5) {
+        writer.write_all(piece).unwrap();
+    }
+    let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+    let expected: Vec<String> = SAMPLES.iter().map(|input| V4_URL.encode(input).unwrap()).collect();
+    assert_eq!(output, expected.join("\n") + "\n");
+}
+
+#[test]
+fn test_streaming_writer_rejects_foreign_bytes() {
+    let mut writer = TranscodeWriter::new(Vec::new(), &V1_STANDARD, &V2_NUMBERS).unwrap();
+    let error = writer.write_all(b"SGVs#bG8").unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
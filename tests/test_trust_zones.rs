@@ -0,0 +1,62 @@
+use asc100::char::versions::V1_STANDARD;
+use asc100::char::extensions::ExtensionsStrategy;
+use asc100::proto::{decode_trust_segments, trust_segments, Dispatcher, Trust, TrustPolicy};
+use asc100::Asc100Error;
+
+#[test]
+fn test_segments_are_labeled() {
+    let segments = trust_segments("hi #TR#safe#TR# then #DNT#evil#DNT#").unwrap();
+    let labels: Vec<_> = segments.iter().map(|s| (s.trust, s.text.as_str(), s.position)).collect();
+
+    assert_eq!(labels, vec![
+        (Trust::Unlabeled, "hi ", 0),
+        (Trust::Trusted, "safe", 7),
+        (Trust::Unlabeled, " then ", 15),
+        (Trust::Untrusted, "evil", 26),
+    ]);
+}
+
+#[test]
+fn test_untrusted_inside_trusted_is_a_downgrade() {
+    let segments = trust_segments("#TR#cmd #DNT#user input#DNT# end#TR#").unwrap();
+    let labels: Vec<_> = segments.iter().map(|s| s.trust).collect();
+    assert_eq!(labels, vec![Trust::Trusted, Trust::Untrusted, Trust::Trusted]);
+}
+
+#[test]
+fn test_mislabeled_nesting_is_detected() {
+    for input in ["#DNT#a #TR#b#TR# c#DNT#", "#TR#a #DNT#b#TR# c#DNT#", "#TR#never closed"] {
+        assert!(
+            matches!(trust_segments(input), Err(Asc100Error::MislabeledTrust { .. })),
+            "Expected mislabel for {}", input
+        );
+    }
+}
+
+#[test]
+fn test_policies_refuse_or_redact() {
+    let strategy = ExtensionsStrategy::strict();
+    let encoded = V1_STANDARD.encode_with("#TR#name=#TR##DNT#bobby#DNT#", &strategy).unwrap();
+    let segments = decode_trust_segments(&encoded, &V1_STANDARD).unwrap();
+
+    assert_eq!(TrustPolicy::redact_untrusted("[redacted]").apply(&segments).unwrap(), "name=[redacted]");
+    assert!(matches!(
+        TrustPolicy::refuse_untrusted().apply(&segments),
+        Err(Asc100Error::UntrustedContent { trust: Trust::Untrusted, position: 18 })
+    ));
+    assert!(TrustPolicy::trusted_only().apply(&trust_segments("plain").unwrap()).is_err());
+}
+
+#[test]
+fn test_policy_guards_dispatch() {
+    let mut dispatcher = Dispatcher::new();
+    dispatcher.register("echo", |args| Ok(args.join(",")));
+
+    let encoded = V1_STANDARD
+        .encode_with("#TR##FX#echo#ARG#a#TR##DNT##ARG#injected#DNT#", &ExtensionsStrategy::strict())
+        .unwrap();
+
+    let policy = TrustPolicy::redact_untrusted("");
+    assert_eq!(dispatcher.dispatch_trusted(&encoded, &V1_STANDARD, &policy).unwrap(), vec!["a"]);
+    assert!(dispatcher.dispatch_trusted(&encoded, &V1_STANDARD, &TrustPolicy::refuse_untrusted()).is_err());
+}
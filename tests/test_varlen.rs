@@ -0,0 +1,92 @@
+use std::io::Write;
+
+use asc100::char::extensions::ExtensionsStrategy;
+use asc100::char::versions::{Packing, V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL};
+use asc100::codec::Codec;
+use asc100::transcode::{transcode, TranscodeWriter};
+use asc100::varlen::{code_length, is_variable, VARIABLE_TAG};
+
+const SAMPLES: &[&str] = &[
+    "",
+    "a",
+    "0",
+    "Hello, World! 123",
+    "the quick brown fox jumps over the lazy dog",
+    "https://example.com/path?query=value&x=1",
+    "\tTabs\nand\r\nnewlines~",
+];
+
+#[test]
+fn test_variable_roundtrip_all_versions() {
+    for version in [V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL] {
+        let variable = version.variable();
+        assert_eq!(variable.packing, Packing::Variable);
+        for &input in SAMPLES {
+            let encoded = variable.encode(input).unwrap();
+            assert!(is_variable(&encoded), "{:?}", encoded);
+            assert_eq!(variable.decode(&encoded).unwrap(), input, "{} {:?}", version.name, input);
+            // Decoding accepts either packing regardless of the version's setting
+            assert_eq!(version.decode(&encoded).unwrap(), input);
+        }
+    }
+}
+
+#[test]
+fn test_variable_roundtrip_with_markers() {
+    let strategy = ExtensionsStrategy::strict();
+    let input = "#SSX#value=#V#name#V##ESX##EOF#";
+    let variable = V3_LOWERCASE.variable();
+    let encoded = variable.encode_with(input, &strategy).unwrap();
+    assert!(encoded.starts_with(VARIABLE_TAG));
+    assert_eq!(variable.decode_with(&encoded, &strategy).unwrap(), input);
+}
+
+#[test]
+fn test_ordering_affects_size() {
+    assert_eq!(code_length(0), 5);
+    assert_eq!(code_length(47), 7);
+    assert_eq!(code_length(99), 9);
+    assert_eq!(code_length(127), 7);
+
+    let digits = "31415926535897932384626433832795028841971";
+    let fixed = V2_NUMBERS.encode(digits).unwrap();
+    let variable = V2_NUMBERS.variable().encode(digits).unwrap();
+    assert!(variable.len() < fixed.len(), "{} vs {}", variable.len(), fixed.len());
+
+    let prose = "the quick brown fox jumps over the lazy dog";
+    assert!(V3_LOWERCASE.variable().encode(prose).unwrap().len() < V3_LOWERCASE.encode(prose).unwrap().len());
+    assert!(V3_LOWERCASE.variable().encode(prose).unwrap().len() < V1_STANDARD.variable().encode(prose).unwrap().len());
+}
+
+#[test]
+fn test_codec_canonical_variable_payloads() {
+    let codec = Codec::builder().version(V1_STANDARD.variable()).canonical(true).wrap(8).build().unwrap();
+    let encoded = codec.encode("Hello, World!").unwrap();
+    assert!(encoded.contains('\n'));
+    assert_eq!(codec.decode(&encoded).unwrap(), "Hello, World!");
+
+    // An extra character only carries padding
+    let payload: String = encoded.chars().filter(|&ch| ch != '\n').collect();
+    assert!(codec.decode(&format!("{}/", payload)).is_err());
+
+    assert!(Codec::builder().alphabet(&format!("{}~", &"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+"[..63])).build().is_err());
+}
+
+#[test]
+fn test_transcode_keeps_packing() {
+    for &input in SAMPLES {
+        let v1 = V1_STANDARD.variable().encode(input).unwrap();
+        let v4 = transcode(&v1, &V1_STANDARD, &V4_URL).unwrap();
+        assert_eq!(v4, V4_URL.variable().encode(input).unwrap(), "{:?}", input);
+    }
+
+    let fixed = V1_STANDARD.encode("fixed payload").unwrap();
+    let variable = V1_STANDARD.variable().encode("variable payload").unwrap();
+    let mut writer = TranscodeWriter::new(Vec::new(), &V1_STANDARD, &V3_LOWERCASE).unwrap();
+    writer.write_all(format!("{}\n{}\n", fixed, variable).as_bytes()).unwrap();
+    let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(V3_LOWERCASE.decode(lines[0]).unwrap(), "fixed payload");
+    assert_eq!(V3_LOWERCASE.decode(lines[1]).unwrap(), "variable payload");
+    assert!(is_variable(lines[1]));
+}
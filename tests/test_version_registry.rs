@@ -0,0 +1,95 @@
+use asc100::char::charset::create_base_charset;
+use asc100::char::extensions::ExtensionsStrategy;
+use asc100::char::registry::{
+    decode_auto, decode_auto_with, encode_tagged, encode_tagged_with, register_version, version_by_name, VersionRegistry,
+};
+use asc100::char::versions::{Asc100Version, V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL};
+use asc100::Asc100Error;
+
+#[test]
+fn test_builtins_by_name_and_distinct_fingerprints() {
+    let builtins = [V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL];
+    for version in builtins {
+        assert_eq!(version_by_name(version.name).unwrap().charset, version.charset);
+    }
+    assert!(version_by_name("v9_unknown").is_none());
+
+    let mut fingerprints: Vec<u32> = builtins.iter().map(Asc100Version::fingerprint).collect();
+    fingerprints.sort_unstable();
+    fingerprints.dedup();
+    assert_eq!(fingerprints.len(), 4);
+
+    // Fingerprints cover the control marker and packing as well as the charset
+    let copy = Asc100Version::custom("v1_renamed", V1_STANDARD.charset).unwrap();
+    assert_ne!(copy.fingerprint(), V1_STANDARD.fingerprint());
+    assert_eq!(copy.with_control_marker(V1_STANDARD.control_marker).unwrap().fingerprint(), V1_STANDARD.fingerprint());
+    assert_ne!(V1_STANDARD.variable().fingerprint(), V1_STANDARD.fingerprint());
+}
+
+#[test]
+fn test_tagged_payloads_decode_automatically() {
+    for version in [V1_STANDARD, V2_NUMBERS, V3_LOWERCASE, V4_URL] {
+        let tagged = encode_tagged("Auto 123 detect", &version).unwrap();
+        assert!(tagged.starts_with(&format!("{:08x}:", version.fingerprint())));
+        assert_eq!(decode_auto(&tagged).unwrap(), "Auto 123 detect");
+    }
+
+    // Tags of other packings resolve to the same registered version
+    let tagged = encode_tagged("Auto 123 detect", &V2_NUMBERS.shifted()).unwrap();
+    assert_eq!(decode_auto(&tagged).unwrap(), "Auto 123 detect");
+
+    let strategy = ExtensionsStrategy::strict();
+    let tagged = encode_tagged_with("#V#name#V#", &V4_URL, &strategy).unwrap();
+    assert_eq!(decode_auto_with(&tagged, &strategy).unwrap(), "#V#name#V#");
+}
+
+#[test]
+fn test_custom_versions_register_globally() {
+    let mut charset = create_base_charset();
+    charset.rotate_left(7);
+    let custom = Asc100Version::custom("rotated_registry_test", charset).unwrap();
+
+    let tagged = encode_tagged("custom", &custom).unwrap();
+    assert!(matches!(decode_auto(&tagged), Err(Asc100Error::UnknownVersion(_))));
+
+    register_version(custom).unwrap();
+    register_version(custom).unwrap();
+    assert_eq!(decode_auto(&tagged).unwrap(), "custom");
+    assert!(version_by_name("rotated_registry_test").is_some());
+
+    let clash = Asc100Version::custom("rotated_registry_test", create_base_charset()).unwrap();
+    assert!(matches!(register_version(clash), Err(Asc100Error::DuplicateVersion(_))));
+}
+
+#[test]
+fn test_fingerprint_collisions_are_rejected() {
+    let mut registry = VersionRegistry::new();
+
+    // Same ordering and marker as V1 under another name: tags could not tell them apart
+    let twin = Asc100Version::custom("v1_twin", V1_STANDARD.charset).unwrap()
+        .with_control_marker(V1_STANDARD.control_marker)
+        .unwrap();
+    assert!(matches!(registry.register(twin), Err(Asc100Error::DuplicateVersion(name)) if name == "v1_standard"));
+    assert!(matches!(registry.register(twin.variable()), Err(Asc100Error::DuplicateVersion(_))));
+
+    // Same name and ordering with another marker is a different version
+    let remarked = Asc100Version { name: "v1_standard", ..twin.with_control_marker(127).unwrap() };
+    assert!(matches!(registry.register(remarked), Err(Asc100Error::DuplicateVersion(_))));
+
+    // Its own marker keeps V1's ordering distinct, and decode_auto picks it
+    let custom = Asc100Version::custom("v1_ordering", V1_STANDARD.charset).unwrap();
+    registry.register(custom).unwrap();
+    let tagged = encode_tagged("mine", &custom).unwrap();
+    let (resolved, _) = registry.resolve_tag(&tagged).unwrap();
+    assert_eq!(resolved.name, "v1_ordering");
+    assert_eq!(registry.versions().len(), 5);
+}
+
+#[test]
+fn test_invalid_tags() {
+    let registry = VersionRegistry::new();
+    for tagged in ["no tag at all", "xyz:AAAA", "1234:AAAA"] {
+        assert!(matches!(registry.resolve_tag(tagged), Err(Asc100Error::InvalidVersionTag(_))), "{}", tagged);
+    }
+    assert!(matches!(registry.resolve_tag("00000000:AAAA"), Err(Asc100Error::UnknownVersion(_))));
+}
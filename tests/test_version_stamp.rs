@@ -0,0 +1,64 @@
+use asc100::char::versions::{V1_STANDARD, V2_NUMBERS, V4_URL};
+use asc100::char::extensions::{CoreStrategy, ExtensionsStrategy, VersionedStrategy};
+use asc100::char::charset::{MARKER_X, MARKER_X_V2};
+use asc100::Asc100Error;
+
+#[test]
+fn test_stamped_roundtrip_all_placements() {
+    let input = "Hello #V#name#V# World";
+
+    let prefix = VersionedStrategy::new(ExtensionsStrategy::strict(), &V1_STANDARD);
+    let suffix = VersionedStrategy::new(ExtensionsStrategy::strict(), &V1_STANDARD).suffix();
+
+    for strategy in [prefix, suffix] {
+        let encoded = V1_STANDARD.encode_with(input, &strategy).expect("Should encode with stamp");
+        let decoded = V1_STANDARD.decode_with(&encoded, &strategy).expect("Should decode with stamp");
+        assert_eq!(input, decoded);
+    }
+}
+
+#[test]
+fn test_stamp_adds_control_marker() {
+    let stamped = VersionedStrategy::new(CoreStrategy::strict(), &V1_STANDARD);
+    let encoded = V1_STANDARD.encode_with("abc", &stamped).unwrap();
+
+    // Unstamped extensions decode sees the raw #X# marker in front
+    let raw = V1_STANDARD.decode_with(&encoded, &ExtensionsStrategy::strict()).unwrap();
+    assert_eq!(raw, "#X#abc");
+}
+
+#[test]
+fn test_wrong_version_is_rejected() {
+    let v1 = VersionedStrategy::new(CoreStrategy::strict(), &V1_STANDARD);
+    let v2 = VersionedStrategy::new(CoreStrategy::strict(), &V2_NUMBERS);
+
+    let encoded = V1_STANDARD.encode_with("Data: 12345", &v1).unwrap();
+
+    match V2_NUMBERS.decode_with(&encoded, &v2) {
+        Err(Asc100Error::InvalidVersionStamp { expected, found }) => {
+            assert_eq!(expected, MARKER_X_V2);
+            assert_eq!(found, Some(MARKER_X));
+        }
+        other => panic!("Expected version stamp mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_missing_stamp_is_rejected() {
+    let stamped = VersionedStrategy::new(CoreStrategy::strict(), &V4_URL);
+    let encoded = V4_URL.encode("https://example.com").unwrap();
+
+    let err = V4_URL.decode_with(&encoded, &stamped).unwrap_err();
+    assert!(matches!(err, Asc100Error::InvalidVersionStamp { found: None, .. }));
+    assert!(err.to_string().contains("Missing version stamp"));
+}
+
+#[test]
+fn test_unnamed_stamp_is_rejected_without_versioned_strategy() {
+    let stamped = VersionedStrategy::new(CoreStrategy::strict(), &V2_NUMBERS);
+    let encoded = V2_NUMBERS.encode_with("abc", &stamped).unwrap();
+
+    // #X# for V2 has no marker string, so it can't be rendered or silently dropped
+    let result = V2_NUMBERS.decode_with(&encoded, &ExtensionsStrategy::strict());
+    assert!(matches!(result, Err(Asc100Error::InvalidIndex(MARKER_X_V2))));
+}